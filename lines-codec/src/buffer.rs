@@ -0,0 +1,152 @@
+use crate::Delimiter;
+
+/// Bytes read from the source but not handed out yet.
+///
+/// The source writes straight into the free space at the back. Bytes that
+/// were already searched for a delimiter are not searched again, and unread
+/// bytes are only moved to the front once the back is used up.
+pub(crate) struct LineBuffer<S> {
+    bytes: S,
+    start: usize,
+    end: usize,
+    /// How many bytes after `start` are known to hold no complete delimiter.
+    scanned: usize,
+    /// How much of the delimiter the scanned bytes end with.
+    matched: usize,
+}
+
+impl<S> LineBuffer<S> {
+    pub(crate) const fn new(bytes: S) -> Self {
+        Self {
+            bytes,
+            start: 0,
+            end: 0,
+            scanned: 0,
+            matched: 0,
+        }
+    }
+}
+
+impl<S: AsRef<[u8]> + AsMut<[u8]>> LineBuffer<S> {
+    pub(crate) fn capacity(&self) -> usize {
+        self.bytes.as_ref().len()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    pub(crate) fn is_full(&self) -> bool {
+        self.len() == self.capacity()
+    }
+
+    pub(crate) fn data(&self) -> &[u8] {
+        &self.bytes.as_ref()[self.start..self.end]
+    }
+
+    pub(crate) fn data_mut(&mut self) -> &mut [u8] {
+        &mut self.bytes.as_mut()[self.start..self.end]
+    }
+
+    /// How much of the delimiter the buffered bytes end with, once
+    /// [`find`](Self::find) has come up empty.
+    pub(crate) fn matched(&self) -> usize {
+        self.matched
+    }
+
+    /// Room for the source to read into, see [`commit`](Self::commit).
+    pub(crate) fn free_mut(&mut self) -> &mut [u8] {
+        if self.end == self.capacity() && self.start > 0 {
+            self.bytes.as_mut().copy_within(self.start..self.end, 0);
+            self.end -= self.start;
+            self.start = 0;
+        }
+        &mut self.bytes.as_mut()[self.end..]
+    }
+
+    /// Take in `len` bytes the source wrote into [`free_mut`](Self::free_mut).
+    pub(crate) fn commit(&mut self, len: usize) {
+        self.end += len;
+    }
+
+    /// Length of the first line including its delimiter, if it is complete.
+    pub(crate) fn find(&mut self, delimiter: Delimiter) -> Option<usize> {
+        let unscanned = &self.bytes.as_ref()[self.start + self.scanned..self.end];
+        match delimiter.scan(self.matched, unscanned) {
+            Ok(end) => Some(self.scanned + end),
+            Err(matched) => {
+                self.scanned = self.len();
+                self.matched = matched;
+                None
+            }
+        }
+    }
+
+    /// Drop the first `len` bytes, or all of them if there are fewer.
+    pub(crate) fn consume(&mut self, len: usize) {
+        self.start += len.min(self.len());
+        self.scanned = 0;
+        self.matched = 0;
+        if self.is_empty() {
+            self.clear();
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.start = 0;
+        self.end = 0;
+        self.scanned = 0;
+        self.matched = 0;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn push<S: AsRef<[u8]> + AsMut<[u8]>>(buffer: &mut LineBuffer<S>, bytes: &[u8]) {
+        buffer.free_mut()[..bytes.len()].copy_from_slice(bytes);
+        buffer.commit(bytes.len());
+    }
+
+    #[test]
+    fn resumes_scan_across_reads() {
+        let mut buffer = LineBuffer::new([0; 16]);
+        push(&mut buffer, b"$GP\r");
+        assert_eq!(buffer.find(Delimiter::CRLF), None);
+        assert_eq!(buffer.matched(), 1);
+        push(&mut buffer, b"\nok");
+        assert_eq!(buffer.find(Delimiter::CRLF), Some(5));
+        buffer.consume(5);
+        assert_eq!(buffer.data(), b"ok");
+        assert_eq!(buffer.find(Delimiter::CRLF), None);
+    }
+
+    #[test]
+    fn compacts_when_back_is_used_up() {
+        let mut storage = [0; 8];
+        let mut buffer = LineBuffer::new(&mut storage[..]);
+        push(&mut buffer, b"abc\ndefg");
+        assert_eq!(buffer.find(Delimiter::LF), Some(4));
+        buffer.consume(4);
+        assert_eq!(buffer.free_mut().len(), 4);
+        push(&mut buffer, b"h\n");
+        assert_eq!(buffer.data(), b"defgh\n");
+        assert_eq!(buffer.find(Delimiter::LF), Some(6));
+    }
+
+    #[test]
+    fn consumes_at_most_what_is_buffered() {
+        let mut buffer = LineBuffer::new([0; 8]);
+        push(&mut buffer, b"ab");
+        buffer.consume(5);
+        assert!(buffer.is_empty());
+        push(&mut buffer, b"c\n");
+        assert_eq!(buffer.data(), b"c\n");
+        assert_eq!(buffer.find(Delimiter::LF), Some(2));
+    }
+}
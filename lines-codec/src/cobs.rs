@@ -0,0 +1,268 @@
+use crate::{Delimiter, ReadLine, ReadLineError, WriteLine};
+
+/// Longest run of data one COBS code byte can cover.
+const MAX_RUN: usize = 254;
+
+/// Room needed to encode `len` bytes, without the trailing zero.
+pub const fn max_encoded_len(len: usize) -> usize {
+    len + len / MAX_RUN + 1
+}
+
+/// COBS-encode `data` into `out`, without the trailing zero.
+///
+/// Returns the encoded length, or `None` if `out` is too short.
+pub fn encode(data: &[u8], out: &mut [u8]) -> Option<usize> {
+    let mut len = 0;
+    for (code, run) in Blocks::new(data) {
+        let block = out.get_mut(len..len + 1 + run.len())?;
+        block[0] = code;
+        block[1..].copy_from_slice(run);
+        len += block.len();
+    }
+    Some(len)
+}
+
+/// Decode a COBS frame, without its trailing zero, where it is.
+///
+/// Returns the decoded length, or `None` if the frame is malformed.
+pub fn decode_in_place(frame: &mut [u8]) -> Option<usize> {
+    let (mut read, mut write) = (0, 0);
+    while read < frame.len() {
+        let code = frame[read] as usize;
+        if code == 0 || read + code > frame.len() {
+            return None;
+        }
+        frame.copy_within(read + 1..read + code, write);
+        read += code;
+        write += code - 1;
+        if code <= MAX_RUN && read < frame.len() {
+            frame[write] = 0;
+            write += 1;
+        }
+    }
+    Some(write)
+}
+
+/// The code byte and data of every block `data` encodes to.
+struct Blocks<'a> {
+    rest: Option<&'a [u8]>,
+}
+
+impl<'a> Blocks<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { rest: Some(data) }
+    }
+
+    /// Encoded length including the code bytes.
+    fn encoded_len(data: &[u8]) -> usize {
+        Blocks::new(data).map(|(_, run)| run.len() + 1).sum()
+    }
+}
+
+impl<'a> Iterator for Blocks<'a> {
+    type Item = (u8, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = self.rest?;
+        let zero = rest.iter().take(MAX_RUN).position(|&byte| byte == 0);
+        let (run, next) = match zero {
+            Some(len) => (&rest[..len], Some(&rest[len + 1..])),
+            None if rest.len() >= MAX_RUN => {
+                let (run, next) = rest.split_at(MAX_RUN);
+                // A full block ends the frame without an implicit zero.
+                (run, (!next.is_empty()).then_some(next))
+            }
+            None => (rest, None),
+        };
+        self.rest = next;
+        Some((run.len() as u8 + 1, run))
+    }
+}
+
+/// Reads zero-delimited COBS frames and decodes them in the buffer.
+pub struct CobsReader<R, const SIZE: usize> {
+    lines: ReadLine<R, SIZE>,
+}
+
+impl<R, const SIZE: usize> CobsReader<R, SIZE> {
+    pub fn new(source: R) -> Self {
+        Self {
+            lines: ReadLine::from_source(source)
+                .with_delimiter(Delimiter::NUL)
+                .with_strip_delimiter(true)
+                .with_skip_empty(true),
+        }
+    }
+
+    fn decode<E>(&mut self, len: usize) -> Result<Option<&[u8]>, ReadLineError<E>> {
+        if self.lines.ended() {
+            return Ok(None);
+        }
+        if self.lines.unterminated() {
+            // The source ended in the middle of a frame.
+            return Err(ReadLineError::InvalidFrame);
+        }
+        let frame = self.lines.line_mut(len);
+        let len = decode_in_place(frame).ok_or(ReadLineError::InvalidFrame)?;
+        Ok(Some(&frame[..len]))
+    }
+}
+
+impl<R: embedded_io::Read, const SIZE: usize> CobsReader<R, SIZE> {
+    /// The next decoded frame, or `None` once the source has ended.
+    pub fn next_frame(&mut self) -> Result<Option<&[u8]>, ReadLineError<R::Error>> {
+        let len = self.lines.fill_line()?;
+        self.decode(len)
+    }
+}
+
+impl<R: embedded_io_async::Read, const SIZE: usize> CobsReader<R, SIZE> {
+    /// The next decoded frame, or `None` once the source has ended.
+    pub async fn next_frame_async(&mut self) -> Result<Option<&[u8]>, ReadLineError<R::Error>> {
+        let len = self.lines.fill_line_async().await?;
+        self.decode(len)
+    }
+}
+
+/// Writes COBS frames, each ended by a zero, and buffers them until flushed.
+pub struct CobsWriter<W, const SIZE: usize> {
+    lines: WriteLine<W, SIZE>,
+}
+
+impl<W, const SIZE: usize> CobsWriter<W, SIZE> {
+    pub fn new(sink: W) -> Self {
+        Self {
+            lines: WriteLine::from_sink(sink),
+        }
+    }
+
+    /// Queue `data` if its encoding fits into the free space.
+    fn push(&mut self, data: &[u8]) -> bool {
+        if Blocks::encoded_len(data) + 1 > self.lines.free() {
+            return false;
+        }
+        for (code, run) in Blocks::new(data) {
+            self.lines.append(&[code]);
+            self.lines.append(run);
+        }
+        self.lines.append(&[0]);
+        true
+    }
+}
+
+impl<W: embedded_io::Write, const SIZE: usize> CobsWriter<W, SIZE> {
+    /// Queue a frame, flushing first if the buffer has no room for it.
+    ///
+    /// Frames longer than the whole buffer go straight to the sink.
+    pub fn write_frame(&mut self, data: &[u8]) -> Result<(), W::Error> {
+        if self.push(data) {
+            return Ok(());
+        }
+        self.lines.write_buffered()?;
+        if self.push(data) {
+            return Ok(());
+        }
+        for (code, run) in Blocks::new(data) {
+            self.lines.sink.write_all(&[code])?;
+            self.lines.sink.write_all(run)?;
+        }
+        self.lines.sink.write_all(&[0])
+    }
+
+    pub fn flush(&mut self) -> Result<(), W::Error> {
+        self.lines.flush()
+    }
+}
+
+impl<W: embedded_io_async::Write, const SIZE: usize> CobsWriter<W, SIZE> {
+    /// Queue a frame, flushing first if the buffer has no room for it.
+    ///
+    /// Frames longer than the whole buffer go straight to the sink.
+    pub async fn write_frame_async(&mut self, data: &[u8]) -> Result<(), W::Error> {
+        if self.push(data) {
+            return Ok(());
+        }
+        self.lines.write_buffered_async().await?;
+        if self.push(data) {
+            return Ok(());
+        }
+        for (code, run) in Blocks::new(data) {
+            self.lines.sink.write_all(&[code]).await?;
+            self.lines.sink.write_all(run).await?;
+        }
+        self.lines.sink.write_all(&[0]).await
+    }
+
+    pub async fn flush_async(&mut self) -> Result<(), W::Error> {
+        self.lines.flush_async().await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_case::test_case;
+
+    fn encoded(data: &[u8]) -> Vec<u8> {
+        let mut out = vec![0; max_encoded_len(data.len())];
+        let len = encode(data, &mut out).unwrap();
+        out.truncate(len);
+        out
+    }
+
+    #[test_case(&[] => vec![0x01])]
+    #[test_case(&[0x00] => vec![0x01, 0x01])]
+    #[test_case(&[0x00, 0x00] => vec![0x01, 0x01, 0x01])]
+    #[test_case(&[0x11, 0x22, 0x00, 0x33] => vec![0x03, 0x11, 0x22, 0x02, 0x33])]
+    #[test_case(&[0x11, 0x00, 0x00, 0x00] => vec![0x02, 0x11, 0x01, 0x01, 0x01])]
+    fn encodes(data: &[u8]) -> Vec<u8> {
+        encoded(data)
+    }
+
+    #[test_case(254)]
+    #[test_case(255)]
+    #[test_case(600)]
+    fn round_trips_long_runs(len: usize) {
+        let data: Vec<u8> = (0..len).map(|i| (i % 255) as u8 + 1).collect();
+        let mut frame = encoded(&data);
+        assert!(!frame.contains(&0));
+        assert!(frame.len() <= max_encoded_len(len));
+        assert_eq!(frame.len(), Blocks::encoded_len(&data));
+        let len = decode_in_place(&mut frame).unwrap();
+        assert_eq!(&frame[..len], data);
+    }
+
+    #[test_case(&[0x00] ; "zero code")]
+    #[test_case(&[0x05, 0x11] ; "run past the end")]
+    fn rejects_malformed_frame(frame: &[u8]) {
+        assert_eq!(decode_in_place(&mut frame.to_vec()), None);
+    }
+
+    #[test]
+    fn reads_and_writes_frames() {
+        let mut sink = Vec::new();
+        let mut writer = CobsWriter::<_, 16>::new(&mut sink);
+        writer.write_frame(&[0x11, 0x00, 0x22]).unwrap();
+        writer.write_frame(&[]).unwrap();
+        writer.write_frame(&[0xaa; 20]).unwrap();
+        writer.flush().unwrap();
+
+        let mut stream = sink.clone();
+        stream.extend_from_slice(&[0x00, 0x05, 0x11, 0x00]);
+        let mut reader = CobsReader::<_, 32>::new(&stream[..]);
+        assert_eq!(reader.next_frame(), Ok(Some(&[0x11, 0x00, 0x22][..])));
+        assert_eq!(reader.next_frame(), Ok(Some(&[][..])));
+        assert_eq!(reader.next_frame(), Ok(Some(&[0xaa; 20][..])));
+        assert_eq!(reader.next_frame(), Err(ReadLineError::InvalidFrame));
+        assert_eq!(reader.next_frame(), Ok(None));
+    }
+
+    #[test]
+    fn rejects_frame_cut_off_by_end() {
+        let stream: &[u8] = &[0x03, 0x11, 0x22, 0x00, 0x03, 0x11, 0x22];
+        let mut reader = CobsReader::<_, 16>::new(stream);
+        assert_eq!(reader.next_frame(), Ok(Some(&[0x11, 0x22][..])));
+        assert_eq!(reader.next_frame(), Err(ReadLineError::InvalidFrame));
+        assert_eq!(reader.next_frame(), Ok(None));
+    }
+}
@@ -0,0 +1,83 @@
+/// The byte or byte sequence that ends a line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum Delimiter {
+    Byte(u8),
+    /// Must not be empty.
+    Sequence(&'static [u8]),
+}
+
+impl Default for Delimiter {
+    fn default() -> Self {
+        Self::LF
+    }
+}
+
+impl Delimiter {
+    pub const LF: Self = Self::Byte(b'\n');
+    pub const CR: Self = Self::Byte(b'\r');
+    /// As used by NMEA and most AT command sets.
+    pub const CRLF: Self = Self::Sequence(b"\r\n");
+    pub const NUL: Self = Self::Byte(0);
+
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            Self::Byte(byte) => core::slice::from_ref(byte),
+            Self::Sequence(bytes) => bytes,
+        }
+    }
+
+    /// Look for the delimiter in `bytes`, picking up `matched` bytes into it.
+    ///
+    /// Returns the length up to and including the delimiter, or how much of
+    /// the delimiter the bytes end with.
+    pub(crate) fn scan(&self, mut matched: usize, bytes: &[u8]) -> Result<usize, usize> {
+        let delimiter = self.as_bytes();
+        for (index, &byte) in bytes.iter().enumerate() {
+            matched = self.advance(matched, byte);
+            if matched == delimiter.len() {
+                return Ok(index + 1);
+            }
+        }
+        Err(matched)
+    }
+
+    /// How much of the delimiter is matched after `byte`, given `matched` before it.
+    fn advance(&self, matched: usize, byte: u8) -> usize {
+        let delimiter = self.as_bytes();
+        if delimiter[matched] == byte {
+            return matched + 1;
+        }
+        // Fall back to the longest start of the delimiter that still ends here.
+        (1..=matched)
+            .rev()
+            .find(|&len| {
+                delimiter[len - 1] == byte
+                    && delimiter[matched + 1 - len..matched] == delimiter[..len - 1]
+            })
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(Delimiter::LF, b"$GPGGA\r\n" => Ok(8))]
+    #[test_case(Delimiter::CRLF, b"$GPGGA\r\n" => Ok(8))]
+    #[test_case(Delimiter::CRLF, b"a\rb\r\r\n" => Ok(6))]
+    #[test_case(Delimiter::CRLF, b"$GPGGA\r" => Err(1))]
+    #[test_case(Delimiter::CR, b"OK\r\n" => Ok(3))]
+    #[test_case(Delimiter::NUL, b"\x01\x02" => Err(0))]
+    #[test_case(Delimiter::Sequence(b"aab"), b"xaaab" => Ok(5))]
+    fn finds_delimiter(delimiter: Delimiter, bytes: &[u8]) -> Result<usize, usize> {
+        delimiter.scan(0, bytes)
+    }
+
+    #[test]
+    fn resumes_partial_match() {
+        assert_eq!(Delimiter::CRLF.scan(1, b"\nnext"), Ok(1));
+        assert_eq!(Delimiter::CRLF.scan(1, b"x\r\n"), Ok(3));
+    }
+}
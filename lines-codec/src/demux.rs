@@ -0,0 +1,368 @@
+use crate::buffer::LineBuffer;
+use crate::{OverflowPolicy, ReadLineError, Stats};
+
+const UBX_SYNC: [u8; 2] = [0xb5, 0x62];
+
+/// Sync, class, id and length.
+const UBX_HEADER_LEN: usize = 6;
+
+/// One message from a receiver that mixes NMEA and UBX on one port.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum Frame<'a> {
+    /// A sentence from `$` or `!` to its checksum, without `\r\n`.
+    Nmea(&'a [u8]),
+    /// A UBX message with a valid checksum.
+    Ubx {
+        class: u8,
+        id: u8,
+        payload: &'a [u8],
+    },
+}
+
+/// Splits a byte stream into NMEA sentences and UBX messages.
+///
+/// Bytes that fit neither are skipped up to the next `$`, `!` or UBX sync.
+/// Frames that do not fit into the storage `S` are skipped as well, so its
+/// size also bounds NMEA sentences, which u-blox makes longer than the
+/// standard's 82 bytes.
+pub struct Demux<R, S> {
+    source: R,
+    buffer: LineBuffer<S>,
+    /// Length of the frame handed out last, still in the buffer until the next read.
+    handed_out: usize,
+    stats: Stats,
+    checksum_errors: u32,
+}
+
+/// Where a frame sits in the buffer.
+enum Span {
+    Nmea(usize),
+    Ubx { class: u8, id: u8, len: usize },
+}
+
+/// What the front of the buffer holds.
+enum Parsed {
+    Frame(Span, usize),
+    Skip(usize),
+    /// A UBX message that does not fit into the buffer.
+    TooLong,
+    /// A UBX message with a wrong checksum.
+    Corrupt,
+    Incomplete,
+}
+
+impl<R, const SIZE: usize> Demux<R, [u8; SIZE]> {
+    pub fn new(source: R) -> Self {
+        Self::from_storage(source, [0; SIZE])
+    }
+}
+
+impl<R, S: AsRef<[u8]> + AsMut<[u8]>> Demux<R, S> {
+    /// Split frames in caller-supplied `storage`, such as a slice from a `StaticCell`.
+    pub fn from_storage(source: R, storage: S) -> Self {
+        assert!(!storage.as_ref().is_empty(), "empty storage");
+        Self {
+            source,
+            buffer: LineBuffer::new(storage),
+            handed_out: 0,
+            stats: Stats::default(),
+            checksum_errors: 0,
+        }
+    }
+
+    /// Bytes dropped while looking for the start of a frame so far.
+    pub fn skipped(&self) -> u32 {
+        self.stats.dropped_bytes
+    }
+
+    /// Frames that did not fit into the buffer count as
+    /// [`discarded`](Stats::discarded), skipped bytes as dropped.
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    /// UBX messages dropped for a wrong checksum so far.
+    pub fn checksum_errors(&self) -> u32 {
+        self.checksum_errors
+    }
+
+    /// Drop the frame handed out last.
+    fn release(&mut self) {
+        if self.handed_out > 0 {
+            self.buffer.consume(self.handed_out);
+            self.handed_out = 0;
+        }
+    }
+
+    /// Find a complete frame at the front of the buffer, skipping anything else.
+    ///
+    /// Returns `None` when more bytes are needed.
+    fn find_frame(&mut self) -> Option<Span> {
+        loop {
+            match parse(self.buffer.data(), self.buffer.capacity()) {
+                Parsed::Frame(span, len) => {
+                    self.handed_out = len;
+                    self.stats.emitted(len);
+                    return Some(span);
+                }
+                Parsed::Skip(len) => self.skip(len),
+                Parsed::TooLong => {
+                    self.stats.overflowed(OverflowPolicy::Discard);
+                    self.skip(UBX_SYNC.len());
+                }
+                Parsed::Corrupt => {
+                    self.checksum_errors = self.checksum_errors.saturating_add(1);
+                    self.skip(UBX_SYNC.len());
+                }
+                Parsed::Incomplete if self.buffer.is_full() => {
+                    self.stats.overflowed(OverflowPolicy::Discard);
+                    self.skip(1);
+                }
+                Parsed::Incomplete => return None,
+            }
+        }
+    }
+
+    fn skip(&mut self, len: usize) {
+        self.stats.dropped(len);
+        self.buffer.consume(len);
+    }
+
+    fn source_error<E>(&mut self, error: E) -> ReadLineError<E> {
+        self.stats.source_errors = self.stats.source_errors.saturating_add(1);
+        ReadLineError::Source(error)
+    }
+
+    fn commit(&mut self, len: usize) {
+        self.buffer.commit(len);
+        self.stats.received(len, self.buffer.len());
+    }
+
+    fn frame(&self, span: Span) -> Frame<'_> {
+        let data = self.buffer.data();
+        match span {
+            Span::Nmea(len) => Frame::Nmea(&data[..len]),
+            Span::Ubx { class, id, len } => Frame::Ubx {
+                class,
+                id,
+                payload: &data[UBX_HEADER_LEN..][..len],
+            },
+        }
+    }
+}
+
+impl<R: embedded_io::Read, S: AsRef<[u8]> + AsMut<[u8]>> Demux<R, S> {
+    /// The next frame, or `None` once the source has ended.
+    pub fn next_frame(&mut self) -> Result<Option<Frame<'_>>, ReadLineError<R::Error>> {
+        self.release();
+        loop {
+            if let Some(span) = self.find_frame() {
+                return Ok(Some(self.frame(span)));
+            }
+            let bytes_read = self
+                .source
+                .read(self.buffer.free_mut())
+                .map_err(|e| self.source_error(e))?;
+            if bytes_read == 0 {
+                return Ok(None);
+            }
+            self.commit(bytes_read);
+        }
+    }
+}
+
+impl<R: embedded_io_async::Read, S: AsRef<[u8]> + AsMut<[u8]>> Demux<R, S> {
+    /// The next frame, or `None` once the source has ended.
+    pub async fn next_frame_async(&mut self) -> Result<Option<Frame<'_>>, ReadLineError<R::Error>> {
+        self.release();
+        loop {
+            if let Some(span) = self.find_frame() {
+                return Ok(Some(self.frame(span)));
+            }
+            let bytes_read = self
+                .source
+                .read(self.buffer.free_mut())
+                .await
+                .map_err(|e| self.source_error(e))?;
+            if bytes_read == 0 {
+                return Ok(None);
+            }
+            self.commit(bytes_read);
+        }
+    }
+}
+
+fn parse(data: &[u8], capacity: usize) -> Parsed {
+    match data {
+        [] | [0xb5] => Parsed::Incomplete,
+        [b'$' | b'!', ..] => parse_nmea(data),
+        [0xb5, 0x62, ..] => parse_ubx(data, capacity),
+        [_, rest @ ..] => {
+            let start = rest.iter().position(|b| matches!(b, b'$' | b'!' | 0xb5));
+            Parsed::Skip(start.map_or(data.len(), |start| start + 1))
+        }
+    }
+}
+
+fn parse_nmea(data: &[u8]) -> Parsed {
+    for (index, &byte) in data.iter().enumerate().skip(1) {
+        match byte {
+            b'\n' => {
+                let len = if data[index - 1] == b'\r' {
+                    index - 1
+                } else {
+                    index
+                };
+                return Parsed::Frame(Span::Nmea(len), index + 1);
+            }
+            b'\r' | b' '..=b'~' => {}
+            // Cut short by something else, probably a UBX message.
+            _ => return Parsed::Skip(index),
+        }
+    }
+    Parsed::Incomplete
+}
+
+fn parse_ubx(data: &[u8], capacity: usize) -> Parsed {
+    let Some(header) = data.get(..UBX_HEADER_LEN) else {
+        return Parsed::Incomplete;
+    };
+    let len = u16::from_le_bytes([header[4], header[5]]) as usize;
+    let frame_len = UBX_HEADER_LEN + len + 2;
+    if frame_len > capacity {
+        return Parsed::TooLong;
+    }
+    let Some(frame) = data.get(..frame_len) else {
+        return Parsed::Incomplete;
+    };
+    let (checked, checksum) = frame[UBX_SYNC.len()..].split_at(frame_len - 4);
+    if fletcher(checked) != [checksum[0], checksum[1]] {
+        return Parsed::Corrupt;
+    }
+    let span = Span::Ubx {
+        class: header[2],
+        id: header[3],
+        len,
+    };
+    Parsed::Frame(span, frame_len)
+}
+
+/// The 8-bit Fletcher checksum UBX uses, over class, id, length and payload.
+fn fletcher(bytes: &[u8]) -> [u8; 2] {
+    bytes.iter().fold([0u8, 0u8], |[a, b], byte| {
+        let a = a.wrapping_add(*byte);
+        [a, b.wrapping_add(a)]
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// NAV-POSLLH poll, an empty UBX message.
+    const POLL: &[u8] = &[0xb5, 0x62, 0x01, 0x02, 0x00, 0x00, 0x03, 0x0a];
+
+    fn frames<const SIZE: usize>(stream: &[u8]) -> (Vec<String>, u32, u32) {
+        let mut demux = Demux::<_, [u8; SIZE]>::new(stream);
+        let mut frames = Vec::new();
+        while let Some(frame) = demux.next_frame().unwrap() {
+            frames.push(match frame {
+                Frame::Nmea(line) => String::from_utf8(line.to_vec()).unwrap(),
+                Frame::Ubx { class, id, payload } => {
+                    format!("UBX {class:02x} {id:02x} {payload:?}")
+                }
+            });
+        }
+        (frames, demux.skipped(), demux.checksum_errors())
+    }
+
+    #[test]
+    fn computes_fletcher_checksum() {
+        assert_eq!(fletcher(&POLL[2..6]), [0x03, 0x0a]);
+    }
+
+    #[test]
+    fn splits_mixed_stream() {
+        let mut stream = b"$GPTXT*4F\r\n".to_vec();
+        stream.extend_from_slice(POLL);
+        stream.extend_from_slice(&[0xb5, 0x62, 0x0a, 0x04, 0x02, 0x00, 0x01, 0x02, 0x13, 0x5c]);
+        stream.extend_from_slice(b"$PUBX,00*33\r\n");
+        let (frames, skipped, checksum_errors) = frames::<64>(&stream);
+        assert_eq!(
+            frames,
+            [
+                "$GPTXT*4F",
+                "UBX 01 02 []",
+                "UBX 0a 04 [1, 2]",
+                "$PUBX,00*33"
+            ]
+        );
+        assert_eq!((skipped, checksum_errors), (0, 0));
+    }
+
+    #[test]
+    fn resyncs_after_garbage() {
+        let mut stream = b"\x00\xffGA,1*\r\n$GP".to_vec();
+        stream.extend_from_slice(POLL);
+        stream.extend_from_slice(&[0xb5, 0x62, 0x01, 0x02, 0x00, 0x00, 0x00, 0x00]);
+        stream.extend_from_slice(b"\xb5$GPTXT*4F\n");
+        let (frames, skipped, checksum_errors) = frames::<64>(&stream);
+        assert_eq!(frames, ["UBX 01 02 []", "$GPTXT*4F"]);
+        assert_eq!(checksum_errors, 1);
+        assert_eq!(skipped, 21);
+    }
+
+    #[test]
+    fn skips_frames_larger_than_buffer() {
+        let mut stream = vec![0xb5, 0x62, 0x01, 0x07, 0x5c, 0x00];
+        stream.extend_from_slice(b"$GPTXT*4F\r\n");
+        let (frames, ..) = frames::<32>(&stream);
+        assert_eq!(frames, ["$GPTXT*4F"]);
+    }
+
+    #[test]
+    #[should_panic = "empty storage"]
+    fn rejects_empty_storage() {
+        Demux::from_storage(&b""[..], &mut [][..]);
+    }
+
+    #[test]
+    fn counts_stats() {
+        let mut stream = vec![0xb5, 0x62, 0x01, 0x07, 0x5c, 0x00];
+        stream.extend_from_slice(b"xx$GPTXT*4F\r\n");
+        stream.extend_from_slice(POLL);
+        let mut storage = [0; 32];
+        let mut demux = Demux::from_storage(&stream[..], &mut storage[..]);
+        while demux.next_frame().unwrap().is_some() {}
+        assert_eq!(
+            demux.stats(),
+            Stats {
+                bytes_received: 27,
+                lines: 2,
+                discarded: 1,
+                truncated: 0,
+                failed: 0,
+                dropped_bytes: 8,
+                source_errors: 0,
+                longest_line: 11,
+                peak_fill: 27,
+            }
+        );
+    }
+
+    /// `$PUBX,00`, 111 bytes with `\r\n`.
+    const PUBX: &str = "$PUBX,00,081350.00,4717.113210,N,00833.915187,E,546.589,G3,2.1,2.0,0.007,77.52,0.007,,0.92,1.19,0.77,9,0,0*5F\r\n";
+
+    #[test]
+    fn reads_sentences_longer_than_standard() {
+        assert!(PUBX.len() > 100);
+        let stream = [PUBX, "$GPTXT*4F\r\n"].concat();
+        let (long, skipped, _) = frames::<128>(stream.as_bytes());
+        assert_eq!(long, [PUBX.trim_end(), "$GPTXT*4F"]);
+        assert_eq!(skipped, 0);
+
+        let (short, ..) = frames::<64>(stream.as_bytes());
+        assert_eq!(short, ["$GPTXT*4F"]);
+    }
+}
@@ -0,0 +1,70 @@
+use embedded_io::ErrorKind;
+
+/// Why [`ReadLine`](crate::ReadLine) could not produce a line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum ReadLineError<E> {
+    /// The source failed to deliver bytes.
+    Source(E),
+    /// A line did not fit into the internal buffer, see [`OverflowPolicy::Fail`](crate::OverflowPolicy::Fail).
+    BufferFull,
+    /// A complete line did not fit into the caller's buffer and was dropped.
+    LineTooLong,
+    /// A line was not valid UTF-8 and was dropped.
+    InvalidUtf8,
+    /// The source went quiet, see [`Timeouts`](crate::Timeouts).
+    Timeout,
+    /// A frame was not valid COBS and was dropped.
+    InvalidFrame,
+}
+
+impl<E: embedded_io::Error> ReadLineError<E> {
+    /// Whether reading again may succeed.
+    ///
+    /// UART framing, parity, overrun and break errors show up as
+    /// [`ErrorKind::Other`] and are transient, a source that is gone is not.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Source(e) => matches!(
+                e.kind(),
+                ErrorKind::Other
+                    | ErrorKind::Interrupted
+                    | ErrorKind::TimedOut
+                    | ErrorKind::InvalidData
+            ),
+            // The buffer is too small for the traffic, reading again just fails again.
+            Self::BufferFull => false,
+            Self::LineTooLong | Self::InvalidUtf8 | Self::Timeout | Self::InvalidFrame => true,
+        }
+    }
+}
+
+impl<E: embedded_io::Error> embedded_io::Error for ReadLineError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Source(e) => e.kind(),
+            Self::BufferFull => ErrorKind::OutOfMemory,
+            Self::LineTooLong => ErrorKind::InvalidInput,
+            Self::InvalidUtf8 => ErrorKind::InvalidData,
+            Self::Timeout => ErrorKind::TimedOut,
+            Self::InvalidFrame => ErrorKind::InvalidData,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: core::fmt::Debug> core::fmt::Display for ReadLineError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Source(e) => write!(f, "source error: {e:?}"),
+            Self::BufferFull => f.write_str("line does not fit into the buffer"),
+            Self::LineTooLong => f.write_str("line does not fit into the caller's buffer"),
+            Self::InvalidUtf8 => f.write_str("line is not valid UTF-8"),
+            Self::Timeout => f.write_str("source went quiet"),
+            Self::InvalidFrame => f.write_str("frame is not valid COBS"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: core::fmt::Debug> std::error::Error for ReadLineError<E> {}
@@ -0,0 +1,111 @@
+//! Owned lines for host tools, and sources from `std` and tokio readers.
+
+use futures_util::Stream;
+
+pub use embedded_io_adapters::std::FromStd;
+#[cfg(feature = "tokio")]
+pub use embedded_io_adapters::tokio_1::FromTokio;
+
+use crate::{LineReader, ReadLine, ReadLineError};
+
+/// Iterator over owned lines, see [`LineReader::lines`].
+pub struct Lines<R, S> {
+    reader: LineReader<R, S>,
+}
+
+impl<R, S> Lines<R, S> {
+    pub fn into_inner(self) -> LineReader<R, S> {
+        self.reader
+    }
+}
+
+impl<R: embedded_io::Read, S: AsRef<[u8]> + AsMut<[u8]>> Iterator for Lines<R, S> {
+    type Item = Result<Vec<u8>, ReadLineError<R::Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.reader.fill_line() {
+            Ok(0) if self.reader.ended() => None,
+            Ok(len) => Some(Ok(self.reader.line(len).to_vec())),
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+impl<R: std::io::Read, const SIZE: usize> ReadLine<FromStd<R>, SIZE> {
+    /// Read lines from a file, serial port or anything else implementing [`std::io::Read`].
+    pub fn from_std(reader: R) -> Self {
+        Self::from_source(FromStd::new(reader))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<R: tokio::io::AsyncRead + Unpin, const SIZE: usize> ReadLine<FromTokio<R>, SIZE> {
+    pub fn from_tokio(reader: R) -> Self {
+        Self::from_source(FromTokio::new(reader))
+    }
+}
+
+impl<R: embedded_io::Read, S: AsRef<[u8]> + AsMut<[u8]>> LineReader<R, S> {
+    /// Iterate over owned lines until the source has ended.
+    ///
+    /// Errors are yielded like lines, so the caller decides whether to go on.
+    pub fn lines(self) -> Lines<R, S> {
+        Lines { reader: self }
+    }
+}
+
+impl<R: embedded_io_async::Read, S: AsRef<[u8]> + AsMut<[u8]>> LineReader<R, S> {
+    /// Like [`lines`](Self::lines), as a [`Stream`].
+    pub fn lines_async(self) -> impl Stream<Item = Result<Vec<u8>, ReadLineError<R::Error>>> {
+        futures_util::stream::unfold(self, |mut reader| async move {
+            let line = match reader.fill_line_async().await {
+                Ok(0) if reader.ended() => return None,
+                Ok(len) => Ok(reader.line(len).to_vec()),
+                Err(e) => Err(e),
+            };
+            Some((line, reader))
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures::executor::block_on;
+    use futures_util::StreamExt;
+
+    use super::*;
+    use crate::Delimiter;
+
+    const CAPTURE: &[u8] = b"$GPTXT*4F\r\n\r\n$PUBX,00*33\r\n";
+
+    #[test]
+    fn iterates_over_std_reader() {
+        let reader = ReadLine::<_, 16>::from_std(std::io::Cursor::new(CAPTURE))
+            .with_delimiter(Delimiter::CRLF)
+            .with_strip_delimiter(true);
+        let lines: Result<Vec<_>, _> = reader.lines().collect();
+        assert_eq!(lines.unwrap(), [&b"$GPTXT*4F"[..], b"", b"$PUBX,00*33"]);
+    }
+
+    #[test]
+    fn streams_lines() {
+        let reader = ReadLine::<_, 16>::new_async(CAPTURE);
+        let lines: Vec<_> = block_on(reader.lines_async().collect());
+        assert_eq!(
+            lines,
+            [
+                Ok(b"$GPTXT*4F\r\n".to_vec()),
+                Ok(b"\r\n".to_vec()),
+                Ok(b"$PUBX,00*33\r\n".to_vec())
+            ]
+        );
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn streams_from_tokio_reader() {
+        let reader = ReadLine::<_, 16>::from_tokio(CAPTURE).with_strip_delimiter(true);
+        let lines: Vec<_> = block_on(reader.lines_async().map(Result::unwrap).collect());
+        assert_eq!(lines, [&b"$GPTXT*4F\r"[..], b"\r", b"$PUBX,00*33\r"]);
+    }
+}
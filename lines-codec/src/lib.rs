@@ -1,11 +1,364 @@
-#![cfg_attr(not(test), no_std)]
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
 
+mod buffer;
+mod cobs;
+mod delimiter;
+mod demux;
+mod error;
+#[cfg(feature = "std")]
+mod host;
+mod nmea;
+mod overflow;
 mod read_line;
 mod read_line_async;
+mod stats;
+mod timeout;
+mod write_line;
+mod write_line_async;
 
-pub struct ReadLine<R, const SIZE: usize> {
+use buffer::LineBuffer;
+pub use cobs::{
+    decode_in_place as cobs_decode, encode as cobs_encode, max_encoded_len as cobs_max_encoded_len,
+    CobsReader, CobsWriter,
+};
+pub use delimiter::Delimiter;
+pub use demux::{Demux, Frame};
+pub use error::ReadLineError;
+#[cfg(feature = "tokio")]
+pub use host::FromTokio;
+#[cfg(feature = "std")]
+pub use host::{FromStd, Lines};
+pub use nmea::{
+    checksum as nmea_checksum, NmeaCounters, NmeaError, NmeaReader, Sentence, SentenceError,
+};
+pub use overflow::OverflowPolicy;
+pub use stats::Stats;
+pub use timeout::Timeouts;
+
+/// Reads lines from `R` into the storage `S`, an array or a borrowed slice.
+pub struct LineReader<R, S> {
     source: R,
-    buffer: rbf::RingBuffer<u8, SIZE>,
+    buffer: LineBuffer<S>,
+    delimiter: Delimiter,
+    strip_delimiter: bool,
+    skip_empty: bool,
+    overflow_policy: OverflowPolicy,
+    stats: Stats,
+    /// Progress through the delimiter while skipping the rest of an overflowed line.
+    skipping: Option<usize>,
+    truncated: bool,
+    ended: bool,
+    /// Length of the line handed out last, still in the buffer until the next read.
+    handed_out: usize,
+}
+
+/// A [`LineReader`] with a buffer of `SIZE` bytes inside.
+pub type ReadLine<R, const SIZE: usize> = LineReader<R, [u8; SIZE]>;
+
+impl<R, const SIZE: usize> ReadLine<R, SIZE> {
+    fn from_source(source: R) -> Self {
+        Self::from_storage(source, [0; SIZE])
+    }
+}
+
+impl<R, S: AsRef<[u8]> + AsMut<[u8]>> LineReader<R, S> {
+    /// Read lines into caller-supplied `storage`, such as a slice from a `StaticCell`.
+    pub fn from_storage(source: R, storage: S) -> Self {
+        let delimiter = Delimiter::default();
+        assert!(
+            storage.as_ref().len() > delimiter.as_bytes().len(),
+            "storage too small for a line"
+        );
+        Self {
+            source,
+            buffer: LineBuffer::new(storage),
+            delimiter,
+            strip_delimiter: false,
+            skip_empty: false,
+            overflow_policy: OverflowPolicy::default(),
+            stats: Stats::default(),
+            skipping: None,
+            truncated: false,
+            ended: false,
+            handed_out: 0,
+        }
+    }
+
+    pub fn with_delimiter(mut self, delimiter: Delimiter) -> Self {
+        assert!(!delimiter.as_bytes().is_empty(), "empty delimiter");
+        assert!(
+            self.buffer.capacity() > delimiter.as_bytes().len(),
+            "storage too small for a line"
+        );
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Leave the delimiter out of the lines returned.
+    ///
+    /// An empty line then comes out empty, like the end of the source, see [`ended`](Self::ended).
+    pub fn with_strip_delimiter(mut self, strip: bool) -> Self {
+        self.strip_delimiter = strip;
+        self
+    }
+
+    /// Skip lines that hold nothing but the delimiter.
+    pub fn with_skip_empty(mut self, skip: bool) -> Self {
+        self.skip_empty = skip;
+        self
+    }
+
+    pub fn delimiter(&self) -> Delimiter {
+        self.delimiter
+    }
+
+    pub fn with_overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.overflow_policy = policy;
+        self
+    }
+
+    pub fn overflow_policy(&self) -> OverflowPolicy {
+        self.overflow_policy
+    }
+
+    /// How many lines did not fit into the internal buffer so far.
+    pub fn overflows(&self) -> u32 {
+        self.stats.overflows()
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    /// Whether the last line returned was cut short by [`OverflowPolicy::Truncate`].
+    pub fn truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Whether the last line returned was empty because the source has ended.
+    pub fn ended(&self) -> bool {
+        self.ended
+    }
+
+    /// Drop the line handed out last.
+    fn release(&mut self) {
+        self.ended = false;
+        if self.handed_out > 0 {
+            self.buffer.consume(self.handed_out);
+            self.handed_out = 0;
+        }
+    }
+
+    /// Length of the next complete line in the buffer, if there is one.
+    fn find_line(&mut self) -> Option<usize> {
+        let delimiter_len = self.delimiter.as_bytes().len();
+        let mut end = self.buffer.find(self.delimiter)?;
+        while self.skip_empty && end == delimiter_len {
+            self.buffer.consume(end);
+            end = self.buffer.find(self.delimiter)?;
+        }
+        let len = if self.strip_delimiter {
+            end - delimiter_len
+        } else {
+            end
+        };
+        self.handed_out = end;
+        self.stats.emitted(end);
+        Some(len)
+    }
+
+    /// Length of whatever is left once the source has ended.
+    fn rest(&mut self) -> usize {
+        self.handed_out = self.buffer.len();
+        self.ended = self.handed_out == 0;
+        if !self.ended {
+            self.stats.emitted(self.handed_out);
+        }
+        self.handed_out
+    }
+
+    /// The next line from what is buffered, dealing with a full buffer on the way.
+    ///
+    /// Returns `None` when more bytes are needed from the source.
+    fn buffered_line<E>(&mut self) -> Option<Result<usize, ReadLineError<E>>> {
+        if let Some(len) = self.find_line() {
+            return Some(Ok(len));
+        }
+        if self.buffer.is_full() {
+            return self.overflow();
+        }
+        None
+    }
+
+    /// Deal with a full buffer that holds no delimiter, according to the policy.
+    ///
+    /// Returns `None` when reading should carry on.
+    fn overflow<E>(&mut self) -> Option<Result<usize, ReadLineError<E>>> {
+        self.stats.overflowed(self.overflow_policy);
+        // The buffer may end in the first half of a delimiter.
+        self.skipping = Some(self.buffer.matched());
+        match self.overflow_policy {
+            OverflowPolicy::Discard => {
+                self.stats.dropped(self.buffer.len());
+                self.buffer.clear();
+                None
+            }
+            OverflowPolicy::Truncate => {
+                self.truncated = true;
+                // Leave out the start of a delimiter, `skipping` goes on matching it.
+                let len = self.buffer.len() - self.buffer.matched();
+                self.handed_out = self.buffer.len();
+                self.stats.emitted(self.handed_out);
+                Some(Ok(len))
+            }
+            OverflowPolicy::Fail => {
+                self.stats.dropped(self.buffer.len());
+                self.buffer.clear();
+                Some(Err(ReadLineError::BufferFull))
+            }
+        }
+    }
+
+    fn source_error<E>(&mut self, error: E) -> ReadLineError<E> {
+        self.stats.source_errors = self.stats.source_errors.saturating_add(1);
+        ReadLineError::Source(error)
+    }
+
+    /// Whether the line handed out last stops short of a delimiter, at the end
+    /// of the source or by [`OverflowPolicy::Truncate`].
+    fn unterminated(&self) -> bool {
+        !self.buffer.data()[..self.handed_out].ends_with(self.delimiter.as_bytes())
+    }
+
+    /// Copy the line of `len` bytes into `buf`, or drop it if it does not fit.
+    fn copy_line<E>(&mut self, len: usize, buf: &mut [u8]) -> Result<usize, ReadLineError<E>> {
+        let Some(buf) = buf.get_mut(..len) else {
+            self.stats.rejected(self.handed_out);
+            return Err(ReadLineError::LineTooLong);
+        };
+        buf.copy_from_slice(self.line(len));
+        Ok(len)
+    }
+
+    fn line(&self, len: usize) -> &[u8] {
+        &self.buffer.data()[..len]
+    }
+
+    fn line_str<E>(&self, len: usize) -> Result<&str, ReadLineError<E>> {
+        core::str::from_utf8(self.line(len)).map_err(|_| ReadLineError::InvalidUtf8)
+    }
+
+    fn line_mut(&mut self, len: usize) -> &mut [u8] {
+        &mut self.buffer.data_mut()[..len]
+    }
+
+    /// Take in `len` bytes fresh from the source, skipping the rest of an overflowed line.
+    fn commit(&mut self, len: usize) {
+        self.buffer.commit(len);
+        self.stats.received(len, self.buffer.len());
+        let Some(matched) = self.skipping else {
+            return;
+        };
+        // Nothing else is buffered while skipping.
+        match self.delimiter.scan(matched, self.buffer.data()) {
+            Ok(end) => {
+                self.skipping = None;
+                self.stats.dropped(end);
+                self.buffer.consume(end);
+            }
+            Err(matched) => {
+                self.skipping = Some(matched);
+                self.stats.dropped(self.buffer.len());
+                self.buffer.clear();
+            }
+        }
+    }
+}
+
+impl<R: embedded_io::ErrorType, S> embedded_io::ErrorType for LineReader<R, S> {
+    type Error = ReadLineError<R::Error>;
+}
+
+/// Writes lines, each followed by a delimiter, and buffers them until flushed.
+pub struct WriteLine<W, const SIZE: usize> {
+    sink: W,
+    buffer: [u8; SIZE],
+    len: usize,
+    delimiter: Delimiter,
+    checksum: bool,
+}
+
+impl<W, const SIZE: usize> WriteLine<W, SIZE> {
+    fn from_sink(sink: W) -> Self {
+        Self {
+            sink,
+            buffer: [0; SIZE],
+            len: 0,
+            delimiter: Delimiter::default(),
+            checksum: false,
+        }
+    }
+
+    pub fn with_delimiter(mut self, delimiter: Delimiter) -> Self {
+        assert!(!delimiter.as_bytes().is_empty(), "empty delimiter");
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// End every line with an NMEA `*HH` checksum before the delimiter.
+    pub fn with_checksum(mut self, checksum: bool) -> Self {
+        self.checksum = checksum;
+        self
+    }
+
+    /// Bytes waiting for the next flush.
+    pub fn buffered(&self) -> &[u8] {
+        &self.buffer[..self.len]
+    }
+
+    /// Queue `parts` if they fit, otherwise leave them to the caller.
+    fn push<'a>(&mut self, parts: [&'a [u8]; 3]) -> Option<[&'a [u8]; 3]> {
+        let len: usize = parts.iter().map(|part| part.len()).sum();
+        if len > self.free() {
+            return Some(parts);
+        }
+        for part in parts {
+            self.append(part);
+        }
+        None
+    }
+
+    fn free(&self) -> usize {
+        SIZE - self.len
+    }
+
+    /// Queue `bytes`, which must fit.
+    fn append(&mut self, bytes: &[u8]) {
+        self.buffer[self.len..][..bytes.len()].copy_from_slice(bytes);
+        self.len += bytes.len();
+    }
+
+    /// Drop the first `len` queued bytes once the sink has taken them.
+    fn written(&mut self, len: usize) {
+        self.buffer.copy_within(len..self.len, 0);
+        self.len -= len;
+    }
+}
+
+/// The line, its checksum field if enabled and the delimiter, as they go out.
+fn encode<'a>(
+    line: &'a [u8],
+    checksum: bool,
+    delimiter: &'a Delimiter,
+    field: &'a mut [u8; 3],
+) -> [&'a [u8]; 3] {
+    let field: &[u8] = if checksum {
+        *field = nmea::checksum_field(nmea::checksum(line));
+        field
+    } else {
+        &[]
+    };
+    [line, field, delimiter.as_bytes()]
 }
 
 #[cfg(test)]
@@ -14,7 +367,8 @@ mod test {
 
     use std::collections::VecDeque;
 
-    use embedded_io::{ErrorType, Read};
+    use embedded_io::{ErrorKind, ErrorType, Read};
+    use test_case::test_case;
 
     struct MockReader {
         data: VecDeque<Vec<u8>>,
@@ -22,7 +376,7 @@ mod test {
     }
 
     impl ErrorType for MockReader {
-        type Error = rbf::Error;
+        type Error = ErrorKind;
     }
 
     impl Read for MockReader {
@@ -68,4 +422,410 @@ mod test {
             }
         }
     }
+
+    struct FailingReader {
+        data: VecDeque<Result<Vec<u8>, ErrorKind>>,
+    }
+
+    impl ErrorType for FailingReader {
+        type Error = ErrorKind;
+    }
+
+    impl Read for FailingReader {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+            let Some(data) = self.data.pop_front() else {
+                return Ok(0);
+            };
+            let mut data = data?;
+            if data.len() > buf.len() {
+                self.data.push_front(Ok(data.split_off(buf.len())));
+            }
+            buf[..data.len()].copy_from_slice(&data);
+            Ok(data.len())
+        }
+    }
+
+    #[test]
+    fn propagates_source_error() {
+        let data = vec![
+            Ok(b"$GP".to_vec()),
+            Err(ErrorKind::Other),
+            Ok(b"GGA\n".to_vec()),
+            Err(ErrorKind::NotConnected),
+        ]
+        .into();
+        let mut line_reader = ReadLine::<_, 64>::new(FailingReader { data });
+
+        let mut line = [0u8; 64];
+        let error = line_reader.read_line(&mut line).unwrap_err();
+        assert_eq!(error, ReadLineError::Source(ErrorKind::Other));
+        assert!(error.is_transient());
+
+        let n = line_reader.read_line(&mut line).unwrap();
+        assert_eq!(&line[..n], b"$GPGGA\n");
+
+        let error = line_reader.read_line(&mut line).unwrap_err();
+        assert_eq!(error, ReadLineError::Source(ErrorKind::NotConnected));
+        assert!(!error.is_transient());
+    }
+
+    #[test]
+    fn drops_line_too_long_for_caller() {
+        let data = vec![
+            Ok(b"too ".to_vec()),
+            Ok(b"long".to_vec()),
+            Ok(b"\nok\n".to_vec()),
+        ]
+        .into();
+        let mut line_reader = ReadLine::<_, 64>::new(FailingReader { data });
+
+        let mut line = [0u8; 4];
+        let error = line_reader.read_line(&mut line).unwrap_err();
+        assert_eq!(error, ReadLineError::LineTooLong);
+        assert!(error.is_transient());
+
+        let n = line_reader.read_line(&mut line).unwrap();
+        assert_eq!(&line[..n], b"ok\n");
+        assert_eq!(line_reader.stats().lines, 1);
+        assert_eq!(line_reader.stats().dropped_bytes, 9);
+    }
+
+    fn overlong_line() -> VecDeque<Result<Vec<u8>, ErrorKind>> {
+        vec![Ok(b"$GPGGA,1234".to_vec()), Ok(b"56\nok\n".to_vec())].into()
+    }
+
+    #[test_case(OverflowPolicy::Discard => (Ok(b"ok\n".to_vec()), false))]
+    #[test_case(OverflowPolicy::Truncate => (Ok(b"$GPGGA,1".to_vec()), true))]
+    #[test_case(OverflowPolicy::Fail => (Err(ReadLineError::BufferFull), false))]
+    fn recovers_from_overflow(
+        policy: OverflowPolicy,
+    ) -> (Result<Vec<u8>, ReadLineError<ErrorKind>>, bool) {
+        let source = FailingReader {
+            data: overlong_line(),
+        };
+        let mut line_reader = ReadLine::<_, 8>::new(source).with_overflow_policy(policy);
+
+        let mut line = [0u8; 64];
+        let first = line_reader.read_line(&mut line).map(|n| line[..n].to_vec());
+        let truncated = line_reader.truncated();
+        assert_eq!(line_reader.overflows(), 1);
+        let stats = line_reader.stats();
+        let counted = match policy {
+            OverflowPolicy::Discard => stats.discarded,
+            OverflowPolicy::Truncate => stats.truncated,
+            OverflowPolicy::Fail => stats.failed,
+        };
+        assert_eq!(counted, 1);
+        if let Err(error) = first {
+            // A buffer too small for the traffic does not grow by reading again.
+            assert!(!error.is_transient());
+        }
+
+        let n = line_reader.read_line(&mut line).unwrap();
+        if policy != OverflowPolicy::Discard {
+            assert_eq!(&line[..n], b"ok\n");
+            assert!(!line_reader.truncated());
+        } else {
+            assert_eq!(n, 0);
+        }
+        (first, truncated)
+    }
+
+    #[test_case(false ; "keeping delimiter")]
+    #[test_case(true ; "stripping delimiter")]
+    fn truncates_before_split_delimiter(strip: bool) {
+        let data = vec![Ok(b"abcdefg\r".to_vec()), Ok(b"\nok\r\n".to_vec())].into();
+        let mut line_reader = ReadLine::<_, 8>::new(FailingReader { data })
+            .with_delimiter(Delimiter::CRLF)
+            .with_strip_delimiter(strip)
+            .with_overflow_policy(OverflowPolicy::Truncate);
+
+        assert_eq!(line_reader.next_line(), Ok(&b"abcdefg"[..]));
+        assert!(line_reader.truncated());
+        let ok: &[u8] = if strip { b"ok" } else { b"ok\r\n" };
+        assert_eq!(line_reader.next_line(), Ok(ok));
+        assert_eq!(line_reader.stats().longest_line, 8);
+    }
+
+    #[test]
+    #[should_panic = "storage too small for a line"]
+    fn rejects_storage_without_room_for_a_line() {
+        LineReader::from_storage(&b"\n"[..], &mut [][..]);
+    }
+
+    #[test]
+    #[should_panic = "storage too small for a line"]
+    fn rejects_delimiter_filling_storage() {
+        ReadLine::<_, 2>::new(&b"\r\n"[..]).with_delimiter(Delimiter::CRLF);
+    }
+
+    #[test]
+    fn counts_stats() {
+        let mut data = overlong_line();
+        data.push_back(Err(ErrorKind::Other));
+        data.push_back(Ok(b"abcd\n".to_vec()));
+        let mut line_reader = ReadLine::<_, 8>::new(FailingReader { data });
+
+        let mut line = [0u8; 8];
+        assert_eq!(line_reader.read_line(&mut line), Ok(3));
+        assert!(line_reader.read_line(&mut line).is_err());
+        assert_eq!(line_reader.read_line(&mut line), Ok(5));
+        assert_eq!(line_reader.read_line(&mut line), Ok(0));
+        assert_eq!(
+            line_reader.stats(),
+            Stats {
+                bytes_received: 22,
+                lines: 2,
+                discarded: 1,
+                truncated: 0,
+                failed: 0,
+                dropped_bytes: 14,
+                source_errors: 1,
+                longest_line: 5,
+                peak_fill: 8,
+            }
+        );
+    }
+
+    #[test_case(false => vec![b"$GPGGA,1".to_vec(), vec![], b"OK".to_vec()] ; "keeping empty lines")]
+    #[test_case(true => vec![b"$GPGGA,1".to_vec(), b"OK".to_vec()] ; "skipping empty lines")]
+    fn strips_crlf(skip_empty: bool) -> Vec<Vec<u8>> {
+        let data = vec![Ok(b"$GPGGA,1\r".to_vec()), Ok(b"\n\r\nOK\r\n".to_vec())].into();
+        let mut line_reader = ReadLine::<_, 16>::new(FailingReader { data })
+            .with_delimiter(Delimiter::CRLF)
+            .with_strip_delimiter(true)
+            .with_skip_empty(skip_empty);
+
+        let mut line = [0u8; 8];
+        let mut lines = Vec::new();
+        loop {
+            let n = line_reader.read_line(&mut line).unwrap();
+            if line_reader.ended() {
+                break;
+            }
+            lines.push(line[..n].to_vec());
+        }
+        lines
+    }
+
+    #[test]
+    fn skips_empty_lines_without_stripping() {
+        let data = vec![Ok(b"\na\n\n".to_vec())].into();
+        let mut line_reader = ReadLine::<_, 16>::new(FailingReader { data }).with_skip_empty(true);
+
+        assert_eq!(line_reader.next_line(), Ok(&b"a\n"[..]));
+        assert_eq!(line_reader.next_line(), Ok(&b""[..]));
+        assert!(line_reader.ended());
+    }
+
+    #[test]
+    fn reads_past_caller_buffer_size() {
+        let data = vec![Ok(b"ab\ncd\n".to_vec())].into();
+        let mut line_reader = ReadLine::<_, 16>::new(FailingReader { data });
+
+        let mut line = [0u8; 3];
+        assert_eq!(line_reader.read_line(&mut line), Ok(3));
+        assert!(line_reader.source.data.is_empty());
+        assert_eq!(line_reader.read_line(&mut line), Ok(3));
+        assert_eq!(&line, b"cd\n");
+    }
+
+    #[test]
+    fn borrows_lines() {
+        let data = vec![Ok(b"$GPGGA,1\r\n$GP".to_vec()), Ok(b"RMC\r\n".to_vec())].into();
+        let mut line_reader = ReadLine::<_, 16>::new(FailingReader { data })
+            .with_delimiter(Delimiter::CRLF)
+            .with_strip_delimiter(true);
+
+        assert_eq!(line_reader.next_line(), Ok(&b"$GPGGA,1"[..]));
+        assert_eq!(line_reader.next_line_str(), Ok("$GPRMC"));
+        assert_eq!(line_reader.next_line(), Ok(&b""[..]));
+        assert!(line_reader.ended());
+    }
+
+    #[test]
+    fn reads_into_borrowed_storage() {
+        let data = vec![Ok(b"$GPGGA,1234".to_vec()), Ok(b"\nok\n".to_vec())].into();
+        let mut storage = [0u8; 12];
+        let mut line_reader = LineReader::from_storage(FailingReader { data }, &mut storage[..]);
+
+        assert_eq!(line_reader.next_line(), Ok(&b"$GPGGA,1234\n"[..]));
+        assert_eq!(line_reader.next_line(), Ok(&b"ok\n"[..]));
+        assert_eq!(line_reader.stats().peak_fill, 12);
+    }
+
+    #[test]
+    fn rejects_invalid_utf8() {
+        let data = vec![Ok(b"\xff\n\xc3\xa9\n".to_vec())].into();
+        let mut line_reader = ReadLine::<_, 16>::new(FailingReader { data });
+
+        assert_eq!(line_reader.next_line_str(), Err(ReadLineError::InvalidUtf8));
+        assert_eq!(line_reader.next_line_str(), Ok("é\n"));
+    }
+
+    #[test]
+    fn switches_between_lines_and_bytes() {
+        use embedded_io::BufRead;
+
+        let data = vec![
+            Ok(b"$GPTXT\n\xb5\x62\x01".to_vec()),
+            Ok(b"\x07$GPRMC\n".to_vec()),
+        ]
+        .into();
+        let mut line_reader = ReadLine::<_, 16>::new(FailingReader { data });
+
+        assert_eq!(line_reader.next_line(), Ok(&b"$GPTXT\n"[..]));
+        assert_eq!(line_reader.fill_buf(), Ok(&b"\xb5\x62\x01"[..]));
+        line_reader.consume(2);
+
+        let mut ubx = [0u8; 2];
+        line_reader.read_exact(&mut ubx).unwrap();
+        assert_eq!(ubx, [0x01, 0x07]);
+        assert_eq!(line_reader.next_line(), Ok(&b"$GPRMC\n"[..]));
+    }
+
+    #[test]
+    fn consumes_after_borrowed_line() {
+        use embedded_io::BufRead;
+
+        let data = vec![
+            Ok(b"$GPTXT\n\xb5\x62$GPRMC\n".to_vec()),
+            Ok(b"ok\n".to_vec()),
+        ]
+        .into();
+        let mut line_reader = ReadLine::<_, 32>::new(FailingReader { data });
+
+        assert_eq!(line_reader.next_line(), Ok(&b"$GPTXT\n"[..]));
+        line_reader.consume(2);
+        assert_eq!(line_reader.next_line(), Ok(&b"$GPRMC\n"[..]));
+        line_reader.consume(100);
+        assert_eq!(line_reader.fill_buf(), Ok(&b"ok\n"[..]));
+    }
+
+    #[test]
+    fn consumes_after_borrowed_line_async() {
+        use embedded_io_async::BufRead;
+
+        let mut line_reader = ReadLine::<_, 32>::new_async(&b"$GPTXT\n\xb5\x62$GPRMC\n"[..]);
+        futures::executor::block_on(async {
+            assert_eq!(line_reader.next_line_async().await, Ok(&b"$GPTXT\n"[..]));
+            line_reader.consume(2);
+            assert_eq!(line_reader.next_line_async().await, Ok(&b"$GPRMC\n"[..]));
+            line_reader.consume(100);
+            assert_eq!(line_reader.fill_buf().await, Ok(&b""[..]));
+        });
+    }
+
+    /// Hands out its chunks, with `None` standing for a poll with nothing ready.
+    struct PollReader {
+        data: VecDeque<Option<Vec<u8>>>,
+    }
+
+    impl ErrorType for PollReader {
+        type Error = ErrorKind;
+    }
+
+    impl embedded_io::ReadReady for PollReader {
+        fn read_ready(&mut self) -> Result<bool, Self::Error> {
+            if let Some(None) = self.data.front() {
+                self.data.pop_front();
+                return Ok(false);
+            }
+            Ok(true)
+        }
+    }
+
+    impl Read for PollReader {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+            let Some(Some(data)) = self.data.pop_front() else {
+                return Ok(0);
+            };
+            buf[..data.len()].copy_from_slice(&data);
+            Ok(data.len())
+        }
+    }
+
+    #[test]
+    fn polls_without_blocking() {
+        let data = vec![
+            Some(b"$GP".to_vec()),
+            None,
+            Some(b"GGA\nok".to_vec()),
+            None,
+            Some(b"\n".to_vec()),
+        ]
+        .into();
+        let mut line_reader = ReadLine::<_, 16>::new(PollReader { data });
+
+        assert_eq!(line_reader.poll_line(), Ok(None));
+        assert_eq!(line_reader.poll_line(), Ok(Some(&b"$GPGGA\n"[..])));
+        assert_eq!(line_reader.poll_line(), Ok(None));
+        assert_eq!(line_reader.poll_line(), Ok(Some(&b"ok\n"[..])));
+        assert_eq!(line_reader.poll_line(), Ok(Some(&b""[..])));
+    }
+
+    /// Hands out its chunks, with `None` standing for a read that never completes,
+    /// then waits forever.
+    struct QuietReader {
+        data: VecDeque<Option<Vec<u8>>>,
+    }
+
+    impl ErrorType for QuietReader {
+        type Error = ErrorKind;
+    }
+
+    impl embedded_io_async::Read for QuietReader {
+        async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+            let Some(Some(data)) = self.data.pop_front() else {
+                return core::future::pending().await;
+            };
+            buf[..data.len()].copy_from_slice(&data);
+            Ok(data.len())
+        }
+    }
+
+    /// Expires as soon as it is waited on, remembering how long it was meant to take.
+    #[derive(Default)]
+    struct InstantDelay {
+        expired_ms: Vec<u32>,
+    }
+
+    impl embedded_hal_async::delay::DelayNs for InstantDelay {
+        async fn delay_ns(&mut self, ns: u32) {
+            self.expired_ms.push(ns / 1_000_000);
+        }
+    }
+
+    #[test]
+    fn times_out() {
+        let data = vec![
+            Some(b"ab\n".to_vec()),
+            Some(b"cd".to_vec()),
+            None,
+            Some(b"ef\n".to_vec()),
+        ]
+        .into();
+        let mut line_reader = ReadLine::<_, 16>::new_async(QuietReader { data });
+        let mut delay = InstantDelay::default();
+        let timeouts = Timeouts::new(1000, 20);
+
+        futures::executor::block_on(async {
+            let line = line_reader.next_line_timeout_async(&mut delay, timeouts);
+            assert_eq!(line.await, Ok(&b"ab\n"[..]));
+            assert!(delay.expired_ms.is_empty());
+
+            // The partial line is dropped, and waiting for the next one starts over.
+            let line = line_reader.next_line_timeout_async(&mut delay, timeouts);
+            assert_eq!(line.await, Err(ReadLineError::Timeout));
+            assert_eq!(delay.expired_ms, [20]);
+            assert_eq!(line_reader.stats().dropped_bytes, 2);
+
+            let line = line_reader.next_line_timeout_async(&mut delay, timeouts);
+            assert_eq!(line.await, Ok(&b"ef\n"[..]));
+
+            let line = line_reader.next_line_timeout_async(&mut delay, timeouts);
+            assert_eq!(line.await, Err(ReadLineError::Timeout));
+            assert_eq!(delay.expired_ms, [20, 1000]);
+        });
+    }
 }
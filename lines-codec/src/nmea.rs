@@ -0,0 +1,298 @@
+use crate::{LineReader, ReadLineError};
+
+/// NMEA checksum: the XOR of every byte after the leading `$` or `!`.
+pub fn checksum(sentence: &[u8]) -> u8 {
+    let body = match sentence {
+        [b'$' | b'!', body @ ..] => body,
+        body => body,
+    };
+    body.iter().fold(0, |checksum, byte| checksum ^ byte)
+}
+
+/// The `*HH` that ends a sentence with checksum `checksum`.
+pub(crate) fn checksum_field(checksum: u8) -> [u8; 3] {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+    [
+        b'*',
+        HEX[(checksum >> 4) as usize],
+        HEX[(checksum & 0xf) as usize],
+    ]
+}
+
+/// One NMEA 0183 sentence, borrowed from the line it was read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Sentence<'a> {
+    line: &'a str,
+    talker: &'a str,
+    sentence_type: &'a str,
+    fields: Option<&'a str>,
+}
+
+impl<'a> Sentence<'a> {
+    /// Check the framing and checksum of `line` and split it up.
+    ///
+    /// Proprietary sentences like `$PUBX` have the talker `P`.
+    pub fn parse(line: &'a str) -> Result<Self, SentenceError> {
+        let line = line.trim_end_matches(['\r', '\n']);
+        let body = line
+            .strip_prefix(['$', '!'])
+            .ok_or(SentenceError::Framing)?;
+        let (body, field) = body.rsplit_once('*').ok_or(SentenceError::Framing)?;
+        let expected = parse_hex(field).ok_or(SentenceError::Framing)?;
+        let computed = checksum(body.as_bytes());
+        if expected != computed {
+            return Err(SentenceError::Checksum { expected, computed });
+        }
+
+        let (address, fields) = match body.split_once(',') {
+            Some((address, fields)) => (address, Some(fields)),
+            None => (body, None),
+        };
+        if address.len() < 3 || !address.bytes().all(|b| b.is_ascii_alphanumeric()) {
+            return Err(SentenceError::Framing);
+        }
+        let (talker, sentence_type) =
+            address.split_at(if address.starts_with('P') { 1 } else { 2 });
+        Ok(Self {
+            line,
+            talker,
+            sentence_type,
+            fields,
+        })
+    }
+
+    /// The whole sentence, from `$` to checksum.
+    pub fn line(&self) -> &'a str {
+        self.line
+    }
+
+    /// Like `GP` or `GN`.
+    pub fn talker(&self) -> &'a str {
+        self.talker
+    }
+
+    /// Like `GGA` or `RMC`.
+    pub fn sentence_type(&self) -> &'a str {
+        self.sentence_type
+    }
+
+    pub fn fields(&self) -> impl Iterator<Item = &'a str> {
+        self.fields.into_iter().flat_map(|fields| fields.split(','))
+    }
+
+    pub fn field(&self, index: usize) -> Option<&'a str> {
+        self.fields().nth(index)
+    }
+}
+
+/// Why a line is not a valid NMEA sentence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum SentenceError {
+    /// No `$` or `!` at the start, no `*HH` at the end, or no valid address.
+    Framing,
+    Checksum {
+        expected: u8,
+        computed: u8,
+    },
+}
+
+/// Why [`NmeaReader`] could not produce a sentence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum NmeaError<E> {
+    Line(ReadLineError<E>),
+    Sentence(SentenceError),
+}
+
+impl<E: embedded_io::Error> NmeaError<E> {
+    /// Whether reading again may succeed, see [`ReadLineError::is_transient`].
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Line(e) => e.is_transient(),
+            Self::Sentence(_) => true,
+        }
+    }
+}
+
+/// Checks sentences and counts those that fail, for lines from any reader.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct NmeaCounters {
+    checksum_errors: u32,
+    framing_errors: u32,
+}
+
+impl NmeaCounters {
+    /// Like [`Sentence::parse`], counting `line` if it is not a valid sentence.
+    pub fn check<'a>(&mut self, line: &'a [u8]) -> Result<Sentence<'a>, SentenceError> {
+        let result = core::str::from_utf8(line)
+            .map_err(|_| SentenceError::Framing)
+            .and_then(Sentence::parse);
+        match result {
+            Err(SentenceError::Checksum { .. }) => {
+                self.checksum_errors = self.checksum_errors.saturating_add(1);
+            }
+            Err(SentenceError::Framing) => {
+                self.framing_errors = self.framing_errors.saturating_add(1);
+            }
+            Ok(_) => {}
+        }
+        result
+    }
+
+    /// Sentences dropped for a wrong checksum so far.
+    pub fn checksum_errors(&self) -> u32 {
+        self.checksum_errors
+    }
+
+    /// Lines dropped for not looking like a sentence at all so far.
+    pub fn framing_errors(&self) -> u32 {
+        self.framing_errors
+    }
+}
+
+/// Reads lines and hands out those that are valid NMEA sentences.
+pub struct NmeaReader<R, S> {
+    lines: LineReader<R, S>,
+    counters: NmeaCounters,
+}
+
+impl<R, S> NmeaReader<R, S> {
+    pub fn new(lines: LineReader<R, S>) -> Self {
+        Self {
+            lines,
+            counters: NmeaCounters::default(),
+        }
+    }
+
+    /// Sentences dropped for a wrong checksum so far.
+    pub fn checksum_errors(&self) -> u32 {
+        self.counters.checksum_errors()
+    }
+
+    /// Lines dropped for not looking like a sentence at all so far.
+    pub fn framing_errors(&self) -> u32 {
+        self.counters.framing_errors()
+    }
+
+    pub fn into_inner(self) -> LineReader<R, S> {
+        self.lines
+    }
+}
+
+impl<R, S: AsRef<[u8]> + AsMut<[u8]>> NmeaReader<R, S> {
+    fn sentence<E>(
+        &mut self,
+        len: Result<usize, ReadLineError<E>>,
+    ) -> Result<Option<Sentence<'_>>, NmeaError<E>> {
+        match len {
+            Ok(0) if self.lines.ended() => Ok(None),
+            Ok(len) => self
+                .counters
+                .check(self.lines.line(len))
+                .map(Some)
+                .map_err(NmeaError::Sentence),
+            Err(e) => Err(NmeaError::Line(e)),
+        }
+    }
+}
+
+impl<R: embedded_io::Read, S: AsRef<[u8]> + AsMut<[u8]>> NmeaReader<R, S> {
+    /// The next valid sentence, or `None` once the source has ended.
+    pub fn next_sentence(&mut self) -> Result<Option<Sentence<'_>>, NmeaError<R::Error>> {
+        let len = self.lines.fill_line();
+        self.sentence(len)
+    }
+}
+
+impl<R: embedded_io_async::Read, S: AsRef<[u8]> + AsMut<[u8]>> NmeaReader<R, S> {
+    /// The next valid sentence, or `None` once the source has ended.
+    pub async fn next_sentence_async(
+        &mut self,
+    ) -> Result<Option<Sentence<'_>>, NmeaError<R::Error>> {
+        let len = self.lines.fill_line_async().await;
+        self.sentence(len)
+    }
+}
+
+fn parse_hex(field: &str) -> Option<u8> {
+    // `from_str_radix` would take a sign as well.
+    if field.len() != 2 || !field.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    u8::from_str_radix(field, 16).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ReadLine;
+    use test_case::test_case;
+
+    #[test_case(b"$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,," => 0x47)]
+    #[test_case(b"$PUBX,00" => 0x33)]
+    #[test_case(b"PUBX,00" => 0x33; "without start")]
+    #[test_case(b"!AIVDM" => 0x57)]
+    #[test_case(b"$" => 0)]
+    fn computes_checksum(sentence: &[u8]) -> u8 {
+        checksum(sentence)
+    }
+
+    #[test]
+    fn formats_checksum_field() {
+        assert_eq!(&checksum_field(0x0a), b"*0A");
+        assert_eq!(&checksum_field(0x47), b"*47");
+    }
+
+    #[test]
+    fn parses_sentence() {
+        let sentence = Sentence::parse(
+            "$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47\r\n",
+        )
+        .unwrap();
+        assert_eq!(sentence.talker(), "GP");
+        assert_eq!(sentence.sentence_type(), "GGA");
+        assert_eq!(sentence.field(1), Some("4807.038"));
+        assert_eq!(sentence.fields().count(), 14);
+        assert!(sentence.line().ends_with("*47"));
+    }
+
+    #[test]
+    fn parses_proprietary_sentence() {
+        let sentence = Sentence::parse("$PUBX,00*33").unwrap();
+        assert_eq!(sentence.talker(), "P");
+        assert_eq!(sentence.sentence_type(), "UBX");
+        assert_eq!(sentence.fields().collect::<Vec<_>>(), ["00"]);
+    }
+
+    #[test_case("GPGGA,1*47" => SentenceError::Framing; "no start")]
+    #[test_case("$GPGGA,1" => SentenceError::Framing; "no checksum")]
+    #[test_case("$GPGGA,1*4" => SentenceError::Framing; "short checksum")]
+    #[test_case("$GPTXT*+F" => SentenceError::Framing; "signed checksum")]
+    #[test_case("$G,1*5A" => SentenceError::Framing; "short address")]
+    #[test_case("$PUBX,00*34" => SentenceError::Checksum { expected: 0x34, computed: 0x33 })]
+    fn rejects_sentence(line: &str) -> SentenceError {
+        Sentence::parse(line).unwrap_err()
+    }
+
+    #[test]
+    fn counts_bad_sentences() {
+        let source: &[u8] = b"$PUBX,00*33\r\n$PUBX,00*34\r\nnoise\r\n\xff\r\n$GPTXT*4F\r\n";
+        let mut reader = NmeaReader::new(ReadLine::<_, 32>::new(source));
+
+        let mut types = Vec::new();
+        loop {
+            match reader.next_sentence() {
+                Ok(Some(sentence)) => types.push(sentence.sentence_type().to_string()),
+                Ok(None) => break,
+                Err(NmeaError::Sentence(_)) => continue,
+                Err(e) => panic!("{e:?}"),
+            }
+        }
+        assert_eq!(types, ["UBX", "TXT"]);
+        assert_eq!(reader.checksum_errors(), 1);
+        assert_eq!(reader.framing_errors(), 2);
+    }
+}
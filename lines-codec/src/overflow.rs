@@ -0,0 +1,16 @@
+/// What [`ReadLine`](crate::ReadLine) does with a line that does not fit
+/// into its internal buffer.
+///
+/// Whatever the policy, the rest of the line up to the next delimiter is
+/// dropped, so reading picks up again at the start of the following line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum OverflowPolicy {
+    /// Drop the line and carry on with the next one.
+    #[default]
+    Discard,
+    /// Return what was buffered so far, see [`ReadLine::truncated`](crate::ReadLine::truncated).
+    Truncate,
+    /// Return [`ReadLineError::BufferFull`](crate::ReadLineError::BufferFull).
+    Fail,
+}
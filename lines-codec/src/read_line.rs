@@ -1,42 +1,121 @@
-use embedded_io::Read;
-
-use crate::ReadLine;
+use crate::{LineReader, ReadLine, ReadLineError};
 
 impl<R: embedded_io::Read, const SIZE: usize> ReadLine<R, SIZE> {
     pub fn new(source: R) -> Self {
-        Self {
-            source,
-            buffer: rbf::RingBuffer::<u8, SIZE>::new(),
-        }
+        Self::from_source(source)
+    }
+}
+
+impl<R: embedded_io::Read, S: AsRef<[u8]> + AsMut<[u8]>> LineReader<R, S> {
+    /// Copy the next line into `buf`, returning its length or 0 once the source has ended.
+    ///
+    /// With [`with_strip_delimiter`](Self::with_strip_delimiter), check [`ended`](Self::ended) to tell an empty line from the end.
+    pub fn read_line(&mut self, buf: &mut [u8]) -> Result<usize, ReadLineError<R::Error>> {
+        let len = self.fill_line()?;
+        self.copy_line(len, buf)
+    }
+
+    /// Borrow the next line straight from the internal buffer, empty once the source has ended.
+    pub fn next_line(&mut self) -> Result<&[u8], ReadLineError<R::Error>> {
+        let len = self.fill_line()?;
+        Ok(self.line(len))
     }
 
-    pub fn read_line(&mut self, buf: &mut [u8]) -> Result<usize, rbf::Error> {
+    /// Like [`next_line`](Self::next_line), for lines that must be UTF-8.
+    pub fn next_line_str(&mut self) -> Result<&str, ReadLineError<R::Error>> {
+        let len = self.fill_line()?;
+        self.line_str(len)
+    }
+
+    /// Read until a line is at the front of the buffer, returning its length.
+    pub(crate) fn fill_line(&mut self) -> Result<usize, ReadLineError<R::Error>> {
+        self.release();
+        self.truncated = false;
         loop {
-            // Check if the buffer contains a newline
-            if let Some(pos) = self.buffer.iter().position(|&b| b == b'\n') {
-                let len = self.buffer.read(&mut buf[..=pos])?;
-                return Ok(len);
+            if let Some(line) = self.buffered_line() {
+                return line;
             }
 
             // Otherwise, read more data from the source
-            // let bytes_read = self.source.read(buf).map_err(|e| rbf::Error::BufferFull)?;
-            let Ok(bytes_read) = self.source.read(buf) else {
-                continue;
-            };
+            let bytes_read = self
+                .source
+                .read(self.buffer.free_mut())
+                .map_err(|e| self.source_error(e))?;
+            if bytes_read == 0 {
+                // EOF reached, return the remaining data as the last line
+                return Ok(self.rest());
+            }
+            self.commit(bytes_read);
+        }
+    }
+}
+
+impl<R: embedded_io::Read + embedded_io::ReadReady, S: AsRef<[u8]> + AsMut<[u8]>> LineReader<R, S> {
+    /// Like [`next_line`](Self::next_line), but only reads what the source has ready.
+    ///
+    /// Returns `Ok(None)` instead of blocking when no complete line is buffered yet,
+    /// so one loop can poll several sources.
+    pub fn poll_line(&mut self) -> Result<Option<&[u8]>, ReadLineError<R::Error>> {
+        let Some(len) = self.poll_fill_line()? else {
+            return Ok(None);
+        };
+        Ok(Some(self.line(len)))
+    }
+
+    fn poll_fill_line(&mut self) -> Result<Option<usize>, ReadLineError<R::Error>> {
+        self.release();
+        self.truncated = false;
+        loop {
+            if let Some(line) = self.buffered_line() {
+                return line.map(Some);
+            }
+
+            if !self.source.read_ready().map_err(|e| self.source_error(e))? {
+                return Ok(None);
+            }
+            let bytes_read = self
+                .source
+                .read(self.buffer.free_mut())
+                .map_err(|e| self.source_error(e))?;
             if bytes_read == 0 {
-                // EOF reached
-                if self.buffer.is_empty() {
-                    return Ok(0); // No more data to read
-                }
-                // Return the remaining data as the last line
-                let count = self.buffer.read(buf)?;
-                return Ok(count);
+                return Ok(Some(self.rest()));
             }
+            self.commit(bytes_read);
+        }
+    }
+}
 
-            // Write data into the ring buffer
-            for byte in &buf[..bytes_read] {
-                self.buffer.push_unless_full(*byte)?;
+/// Hands out buffered bytes before reading on, so the source can switch
+/// between lines and binary data without losing anything.
+impl<R: embedded_io::Read, S: AsRef<[u8]> + AsMut<[u8]>> embedded_io::BufRead for LineReader<R, S> {
+    fn fill_buf(&mut self) -> Result<&[u8], Self::Error> {
+        self.release();
+        while self.buffer.is_empty() {
+            let bytes_read = self
+                .source
+                .read(self.buffer.free_mut())
+                .map_err(|e| self.source_error(e))?;
+            if bytes_read == 0 {
+                break;
             }
+            self.commit(bytes_read);
         }
+        Ok(self.buffer.data())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        // Counts from after the line handed out last, like `fill_buf` does.
+        self.release();
+        self.buffer.consume(amt);
+    }
+}
+
+impl<R: embedded_io::Read, S: AsRef<[u8]> + AsMut<[u8]>> embedded_io::Read for LineReader<R, S> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let data = embedded_io::BufRead::fill_buf(self)?;
+        let len = data.len().min(buf.len());
+        buf[..len].copy_from_slice(&data[..len]);
+        embedded_io::BufRead::consume(self, len);
+        Ok(len)
     }
 }
@@ -1,42 +1,142 @@
-use embedded_io::Read;
+use embedded_hal_async::delay::DelayNs;
 
-use crate::ReadLine;
+use crate::timeout::{with_timeout, Forever};
+use crate::{LineReader, ReadLine, ReadLineError, Timeouts};
 
 impl<R: embedded_io_async::Read, const SIZE: usize> ReadLine<R, SIZE> {
     pub fn new_async(source: R) -> Self {
-        Self {
-            source,
-            buffer: rbf::RingBuffer::<u8, SIZE>::new(),
-        }
+        Self::from_source(source)
+    }
+}
+
+impl<R: embedded_io_async::Read, S: AsRef<[u8]> + AsMut<[u8]>> LineReader<R, S> {
+    /// Copy the next line into `buf`, returning its length or 0 once the source has ended.
+    pub async fn read_line_async(
+        &mut self,
+        buf: &mut [u8],
+    ) -> Result<usize, ReadLineError<R::Error>> {
+        let len = self.fill_line_async().await?;
+        self.copy_line(len, buf)
+    }
+
+    /// Borrow the next line straight from the internal buffer, empty once the source has ended.
+    pub async fn next_line_async(&mut self) -> Result<&[u8], ReadLineError<R::Error>> {
+        let len = self.fill_line_async().await?;
+        Ok(self.line(len))
+    }
+
+    /// Like [`next_line_async`](Self::next_line_async), for lines that must be UTF-8.
+    pub async fn next_line_str_async(&mut self) -> Result<&str, ReadLineError<R::Error>> {
+        let len = self.fill_line_async().await?;
+        self.line_str(len)
+    }
+
+    /// Like [`read_line_async`](Self::read_line_async), giving up with
+    /// [`ReadLineError::Timeout`] when the source stays quiet for too long.
+    pub async fn read_line_timeout_async(
+        &mut self,
+        buf: &mut [u8],
+        delay: &mut impl DelayNs,
+        timeouts: Timeouts,
+    ) -> Result<usize, ReadLineError<R::Error>> {
+        let len = self.fill_line_timeout_async(delay, timeouts).await?;
+        self.copy_line(len, buf)
+    }
+
+    /// Like [`next_line_async`](Self::next_line_async), giving up with
+    /// [`ReadLineError::Timeout`] when the source stays quiet for too long.
+    pub async fn next_line_timeout_async(
+        &mut self,
+        delay: &mut impl DelayNs,
+        timeouts: Timeouts,
+    ) -> Result<&[u8], ReadLineError<R::Error>> {
+        let len = self.fill_line_timeout_async(delay, timeouts).await?;
+        Ok(self.line(len))
     }
 
-    pub async fn read_line_async(&mut self, buf: &mut [u8]) -> Result<usize, rbf::Error> {
+    pub(crate) async fn fill_line_async(&mut self) -> Result<usize, ReadLineError<R::Error>> {
+        self.fill_line_timeout_async(&mut Forever, Timeouts::default())
+            .await
+    }
+
+    /// Read until a line is at the front of the buffer, returning its length.
+    async fn fill_line_timeout_async(
+        &mut self,
+        delay: &mut impl DelayNs,
+        timeouts: Timeouts,
+    ) -> Result<usize, ReadLineError<R::Error>> {
+        self.release();
+        self.truncated = false;
         loop {
-            // Check if the buffer contains a newline
-            if let Some(pos) = self.buffer.iter().position(|&b| b == b'\n') {
-                let len = self.buffer.read(&mut buf[..=pos])?;
-                return Ok(len);
+            if let Some(line) = self.buffered_line() {
+                return line;
             }
 
             // Otherwise, read more data from the source
-            // let bytes_read = self.source.read(buf).map_err(|e| rbf::Error::BufferFull)?;
-            let Ok(bytes_read) = self.source.read(buf).await else {
-                continue;
+            let timeout = if self.buffer.is_empty() {
+                timeouts.idle_ms
+            } else {
+                timeouts.inter_byte_ms
             };
+            let read = self.source.read(self.buffer.free_mut());
+            let bytes_read = match timeout {
+                None => read.await,
+                Some(ms) => match with_timeout(read, delay.delay_ms(ms)).await {
+                    Some(bytes_read) => bytes_read,
+                    None => {
+                        self.stats.dropped(self.buffer.len());
+                        self.buffer.clear();
+                        self.skipping = None;
+                        return Err(ReadLineError::Timeout);
+                    }
+                },
+            }
+            .map_err(|e| self.source_error(e))?;
             if bytes_read == 0 {
-                // EOF reached
-                if self.buffer.is_empty() {
-                    return Ok(0); // No more data to read
-                }
-                // Return the remaining data as the last line
-                let count = self.buffer.read(buf)?;
-                return Ok(count);
+                // EOF reached, return the remaining data as the last line
+                return Ok(self.rest());
             }
+            self.commit(bytes_read);
+        }
+    }
+}
 
-            // Write data into the ring buffer
-            for byte in &buf[..bytes_read] {
-                self.buffer.push_unless_full(*byte)?;
+/// Hands out buffered bytes before reading on, so the source can switch
+/// between lines and binary data without losing anything.
+impl<R: embedded_io_async::Read, S: AsRef<[u8]> + AsMut<[u8]>> embedded_io_async::BufRead
+    for LineReader<R, S>
+{
+    async fn fill_buf(&mut self) -> Result<&[u8], Self::Error> {
+        self.release();
+        while self.buffer.is_empty() {
+            let bytes_read = self
+                .source
+                .read(self.buffer.free_mut())
+                .await
+                .map_err(|e| self.source_error(e))?;
+            if bytes_read == 0 {
+                break;
             }
+            self.commit(bytes_read);
         }
+        Ok(self.buffer.data())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        // Counts from after the line handed out last, like `fill_buf` does.
+        self.release();
+        self.buffer.consume(amt);
+    }
+}
+
+impl<R: embedded_io_async::Read, S: AsRef<[u8]> + AsMut<[u8]>> embedded_io_async::Read
+    for LineReader<R, S>
+{
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let data = embedded_io_async::BufRead::fill_buf(self).await?;
+        let len = data.len().min(buf.len());
+        buf[..len].copy_from_slice(&data[..len]);
+        embedded_io_async::BufRead::consume(self, len);
+        Ok(len)
     }
 }
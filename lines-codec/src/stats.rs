@@ -0,0 +1,62 @@
+use crate::OverflowPolicy;
+
+/// Counters kept by [`ReadLine`](crate::ReadLine) and [`Demux`](crate::Demux) to size their buffers from real traffic.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Stats {
+    /// Bytes read from the source.
+    pub bytes_received: u32,
+    /// Lines handed out, including truncated ones and the last one at the end of the source.
+    pub lines: u32,
+    /// Lines that did not fit into the internal buffer and were dropped, see [`OverflowPolicy::Discard`](crate::OverflowPolicy::Discard).
+    pub discarded: u32,
+    /// Lines that did not fit into the internal buffer and were cut short, see [`OverflowPolicy::Truncate`](crate::OverflowPolicy::Truncate).
+    pub truncated: u32,
+    /// Lines that did not fit into the internal buffer and failed the read, see [`OverflowPolicy::Fail`](crate::OverflowPolicy::Fail).
+    pub failed: u32,
+    /// Bytes of overflowing lines that were thrown away.
+    pub dropped_bytes: u32,
+    pub source_errors: u32,
+    /// Most bytes a line or frame took up in the internal buffer, delimiter included.
+    pub longest_line: usize,
+    /// Most bytes the internal buffer held at once.
+    pub peak_fill: usize,
+}
+
+impl Stats {
+    /// Lines that did not fit into the internal buffer, whatever the policy.
+    pub fn overflows(&self) -> u32 {
+        self.discarded
+            .saturating_add(self.truncated)
+            .saturating_add(self.failed)
+    }
+
+    pub(crate) fn received(&mut self, len: usize, fill: usize) {
+        self.bytes_received = self.bytes_received.saturating_add(len as u32);
+        self.peak_fill = self.peak_fill.max(fill);
+    }
+
+    pub(crate) fn emitted(&mut self, len: usize) {
+        self.lines = self.lines.saturating_add(1);
+        self.longest_line = self.longest_line.max(len);
+    }
+
+    /// A line counted by [`emitted`](Self::emitted) was thrown away after all.
+    pub(crate) fn rejected(&mut self, len: usize) {
+        self.lines = self.lines.saturating_sub(1);
+        self.dropped(len);
+    }
+
+    pub(crate) fn overflowed(&mut self, policy: OverflowPolicy) {
+        let count = match policy {
+            OverflowPolicy::Discard => &mut self.discarded,
+            OverflowPolicy::Truncate => &mut self.truncated,
+            OverflowPolicy::Fail => &mut self.failed,
+        };
+        *count = count.saturating_add(1);
+    }
+
+    pub(crate) fn dropped(&mut self, len: usize) {
+        self.dropped_bytes = self.dropped_bytes.saturating_add(len as u32);
+    }
+}
@@ -0,0 +1,51 @@
+use core::future::{poll_fn, Future};
+use core::pin::pin;
+use core::task::Poll;
+
+use embedded_hal_async::delay::DelayNs;
+
+/// How long async reads wait for the source, `None` meaning forever.
+///
+/// The idle timeout applies while no byte of the next line has arrived, the
+/// inter-byte timeout once it has started. When the inter-byte timeout hits,
+/// the partial line is dropped and counted in [`Stats::dropped_bytes`](crate::Stats::dropped_bytes).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Timeouts {
+    pub idle_ms: Option<u32>,
+    pub inter_byte_ms: Option<u32>,
+}
+
+impl Timeouts {
+    pub const fn new(idle_ms: u32, inter_byte_ms: u32) -> Self {
+        Self {
+            idle_ms: Some(idle_ms),
+            inter_byte_ms: Some(inter_byte_ms),
+        }
+    }
+}
+
+/// A delay that never ends, for reads without a timeout.
+pub(crate) struct Forever;
+
+impl DelayNs for Forever {
+    async fn delay_ns(&mut self, _ns: u32) {
+        core::future::pending().await
+    }
+}
+
+/// Run `future`, or give up with `None` once `timeout` completes first.
+pub(crate) async fn with_timeout<F: Future>(
+    future: F,
+    timeout: impl Future<Output = ()>,
+) -> Option<F::Output> {
+    let mut future = pin!(future);
+    let mut timeout = pin!(timeout);
+    poll_fn(|cx| {
+        if let Poll::Ready(output) = future.as_mut().poll(cx) {
+            return Poll::Ready(Some(output));
+        }
+        timeout.as_mut().poll(cx).map(|()| None)
+    })
+    .await
+}
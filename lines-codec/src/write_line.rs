@@ -0,0 +1,188 @@
+use crate::{encode, WriteLine};
+
+impl<W: embedded_io::Write, const SIZE: usize> WriteLine<W, SIZE> {
+    pub fn new(sink: W) -> Self {
+        Self::from_sink(sink)
+    }
+
+    /// Queue `line`, flushing first if the buffer has no room for it.
+    ///
+    /// Lines longer than the whole buffer go straight to the sink.
+    pub fn write_line(&mut self, line: &[u8]) -> Result<(), W::Error> {
+        let mut field = [0; 3];
+        let delimiter = self.delimiter;
+        let parts = encode(line, self.checksum, &delimiter, &mut field);
+        let Some(parts) = self.push(parts) else {
+            return Ok(());
+        };
+        self.write_buffered()?;
+        let Some(parts) = self.push(parts) else {
+            return Ok(());
+        };
+        for part in parts {
+            self.sink.write_all(part)?;
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<(), W::Error> {
+        self.write_buffered()?;
+        self.sink.flush()
+    }
+
+    /// Write out the queue, keeping whatever the sink did not take when it fails.
+    pub(crate) fn write_buffered(&mut self) -> Result<(), W::Error> {
+        let mut written = 0;
+        let result = loop {
+            if written == self.len {
+                break Ok(());
+            }
+            match self.sink.write(&self.buffer[written..self.len]) {
+                Ok(0) => panic!("write() returned Ok(0)"),
+                Ok(len) => written += len,
+                Err(e) => break Err(e),
+            }
+        };
+        self.written(written);
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::VecDeque;
+
+    use embedded_io::{ErrorKind, ErrorType};
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::Delimiter;
+
+    /// Takes at most as many bytes per write as the next entry says, or fails with it.
+    #[derive(Default)]
+    struct ChoppySink {
+        written: Vec<u8>,
+        writes: VecDeque<Result<usize, ErrorKind>>,
+    }
+
+    impl ChoppySink {
+        fn write(&mut self, buf: &[u8]) -> Result<usize, ErrorKind> {
+            let len = match self.writes.pop_front() {
+                Some(Ok(len)) => len.min(buf.len()),
+                Some(Err(e)) => return Err(e),
+                None => buf.len(),
+            };
+            self.written.extend_from_slice(&buf[..len]);
+            Ok(len)
+        }
+    }
+
+    impl ErrorType for ChoppySink {
+        type Error = ErrorKind;
+    }
+
+    impl embedded_io::Write for ChoppySink {
+        fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+            ChoppySink::write(self, buf)
+        }
+
+        fn flush(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    impl embedded_io_async::Write for ChoppySink {
+        async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+            ChoppySink::write(self, buf)
+        }
+    }
+
+    #[test]
+    fn buffers_until_flush() {
+        let mut sink = Vec::new();
+        let mut writer = WriteLine::<_, 32>::new(&mut sink).with_delimiter(Delimiter::CRLF);
+        writer.write_line(b"AT").unwrap();
+        writer.write_line(b"ATI").unwrap();
+        assert_eq!(writer.buffered(), b"AT\r\nATI\r\n");
+        writer.flush().unwrap();
+        assert_eq!(sink, b"AT\r\nATI\r\n");
+    }
+
+    #[test]
+    fn adds_checksum() {
+        let mut sink = Vec::new();
+        let mut writer = WriteLine::<_, 64>::new(&mut sink)
+            .with_delimiter(Delimiter::CRLF)
+            .with_checksum(true);
+        writer.write_line(b"$PUBX,00").unwrap();
+        writer.flush().unwrap();
+        assert_eq!(sink, b"$PUBX,00*33\r\n");
+    }
+
+    #[test]
+    fn flushes_when_full() {
+        let mut sink = Vec::new();
+        let mut writer = WriteLine::<_, 8>::new(&mut sink);
+        writer.write_line(b"first").unwrap();
+        writer.write_line(b"second").unwrap();
+        assert_eq!(writer.buffered(), b"second\n");
+        writer.write_line(b"much too long").unwrap();
+        assert!(writer.buffered().is_empty());
+        assert_eq!(sink, b"first\nsecond\nmuch too long\n");
+    }
+
+    #[test]
+    fn keeps_unwritten_bytes_after_error() {
+        let sink = ChoppySink {
+            writes: [Ok(3), Ok(2), Err(ErrorKind::Other)].into(),
+            ..Default::default()
+        };
+        let mut writer = WriteLine::<_, 16>::new(sink);
+        writer.write_line(b"first").unwrap();
+        writer.write_line(b"second").unwrap();
+
+        assert_eq!(writer.flush(), Err(ErrorKind::Other));
+        assert_eq!(writer.buffered(), b"\nsecond\n");
+        writer.flush().unwrap();
+        assert_eq!(writer.sink.written, b"first\nsecond\n");
+    }
+
+    #[test]
+    #[should_panic = "empty delimiter"]
+    fn rejects_empty_delimiter() {
+        WriteLine::<_, 8>::new(Vec::new()).with_delimiter(Delimiter::Sequence(b""));
+    }
+
+    #[test]
+    fn writes_lines_async() {
+        let sink = ChoppySink {
+            writes: [Ok(1)].into(),
+            ..Default::default()
+        };
+        let mut writer = WriteLine::<_, 8>::new_async(sink).with_checksum(true);
+        block_on(async {
+            writer.write_line_async(b"$A").await.unwrap();
+            assert_eq!(writer.buffered(), b"$A*41\n");
+            writer.write_line_async(b"$PUBX,00").await.unwrap();
+            assert!(writer.buffered().is_empty());
+            writer.flush_async().await.unwrap();
+        });
+        assert_eq!(writer.sink.written, b"$A*41\n$PUBX,00*33\n");
+    }
+
+    #[test]
+    fn keeps_unwritten_bytes_after_error_async() {
+        let sink = ChoppySink {
+            writes: [Ok(4), Err(ErrorKind::Other)].into(),
+            ..Default::default()
+        };
+        let mut writer = WriteLine::<_, 16>::new_async(sink);
+        block_on(async {
+            writer.write_line_async(b"first").await.unwrap();
+            assert_eq!(writer.flush_async().await, Err(ErrorKind::Other));
+            assert_eq!(writer.buffered(), b"t\n");
+            writer.flush_async().await.unwrap();
+        });
+        assert_eq!(writer.sink.written, b"first\n");
+    }
+}
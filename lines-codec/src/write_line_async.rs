@@ -0,0 +1,49 @@
+use crate::{encode, WriteLine};
+
+impl<W: embedded_io_async::Write, const SIZE: usize> WriteLine<W, SIZE> {
+    pub fn new_async(sink: W) -> Self {
+        Self::from_sink(sink)
+    }
+
+    /// Queue `line`, flushing first if the buffer has no room for it.
+    ///
+    /// Lines longer than the whole buffer go straight to the sink.
+    pub async fn write_line_async(&mut self, line: &[u8]) -> Result<(), W::Error> {
+        let mut field = [0; 3];
+        let delimiter = self.delimiter;
+        let parts = encode(line, self.checksum, &delimiter, &mut field);
+        let Some(parts) = self.push(parts) else {
+            return Ok(());
+        };
+        self.write_buffered_async().await?;
+        let Some(parts) = self.push(parts) else {
+            return Ok(());
+        };
+        for part in parts {
+            self.sink.write_all(part).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn flush_async(&mut self) -> Result<(), W::Error> {
+        self.write_buffered_async().await?;
+        self.sink.flush().await
+    }
+
+    /// Write out the queue, keeping whatever the sink did not take when it fails.
+    pub(crate) async fn write_buffered_async(&mut self) -> Result<(), W::Error> {
+        let mut written = 0;
+        let result = loop {
+            if written == self.len {
+                break Ok(());
+            }
+            match self.sink.write(&self.buffer[written..self.len]).await {
+                Ok(0) => panic!("write() returned Ok(0)"),
+                Ok(len) => written += len,
+                Err(e) => break Err(e),
+            }
+        };
+        self.written(written);
+        result
+    }
+}
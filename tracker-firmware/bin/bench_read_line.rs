@@ -0,0 +1,128 @@
+#![no_std]
+#![no_main]
+
+//! Compares `ReadLine` against the old approach of rescanning the whole
+//! buffer and pushing bytes one by one, fed a few bytes per read like a UART.
+//!
+//! Run it on the board with `cargo run --release --bin bench_read_line`.
+//! The Cortex-M0+ has no cycle counter, so cycles are worked out from the
+//! microsecond timer and the system clock.
+
+use core::convert::Infallible;
+
+use embassy_executor::Spawner;
+use embassy_time::{Instant, Timer};
+use embedded_io::{ErrorType, Read};
+use heapless::Deque;
+use lines_codec::ReadLine;
+use {defmt_rtt as _, panic_probe as _};
+
+const SENTENCE: &[u8] = b"$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47\r\n";
+const LINES: usize = 200;
+const BUFFER_SIZE: usize = 256;
+
+/// Hands out `SENTENCE` over and over, `chunk` bytes per read.
+struct Trickle {
+    position: usize,
+    remaining: usize,
+    chunk: usize,
+}
+
+impl Trickle {
+    fn new(chunk: usize) -> Self {
+        Self {
+            position: 0,
+            remaining: LINES * SENTENCE.len(),
+            chunk,
+        }
+    }
+}
+
+impl ErrorType for Trickle {
+    type Error = Infallible;
+}
+
+impl Read for Trickle {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let len = buf
+            .len()
+            .min(self.chunk)
+            .min(self.remaining)
+            .min(SENTENCE.len() - self.position);
+        buf[..len].copy_from_slice(&SENTENCE[self.position..self.position + len]);
+        self.position = (self.position + len) % SENTENCE.len();
+        self.remaining -= len;
+        Ok(len)
+    }
+}
+
+/// The reader as it was: a full rescan per read, the caller's buffer as scratch.
+struct Naive<R> {
+    source: R,
+    buffer: Deque<u8, BUFFER_SIZE>,
+}
+
+impl<R: Read> Naive<R> {
+    fn read_line(&mut self, buf: &mut [u8]) -> usize {
+        loop {
+            if let Some(pos) = self.buffer.iter().position(|&b| b == b'\n') {
+                for byte in &mut buf[..=pos] {
+                    *byte = self.buffer.pop_front().unwrap();
+                }
+                return pos + 1;
+            }
+            let Ok(bytes_read) = self.source.read(buf) else {
+                continue;
+            };
+            if bytes_read == 0 {
+                return 0;
+            }
+            for byte in &buf[..bytes_read] {
+                self.buffer.push_back(*byte).unwrap();
+            }
+        }
+    }
+}
+
+fn bench_naive(chunk: usize) -> u64 {
+    let mut reader = Naive {
+        source: Trickle::new(chunk),
+        buffer: Deque::new(),
+    };
+    let mut line = [0u8; BUFFER_SIZE];
+    let start = Instant::now();
+    while reader.read_line(&mut line) > 0 {}
+    start.elapsed().as_micros()
+}
+
+fn bench_read_line(chunk: usize) -> u64 {
+    let mut reader = ReadLine::<_, BUFFER_SIZE>::new(Trickle::new(chunk));
+    let mut line = [0u8; BUFFER_SIZE];
+    let start = Instant::now();
+    while let Ok(1..) = reader.read_line(&mut line) {}
+    start.elapsed().as_micros()
+}
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) -> ! {
+    let _p = embassy_rp::init(embassy_rp::config::Config::default());
+
+    let cycles_per_us = (embassy_rp::clocks::clk_sys_freq() / 1_000_000) as u64;
+    let cycles_per_line = |us: u64| us * cycles_per_us / LINES as u64;
+
+    loop {
+        for chunk in [1, 4, 16, 64] {
+            let naive = bench_naive(chunk);
+            let read_line = bench_read_line(chunk);
+            defmt::println!(
+                "{} bytes per read: naive {} cycles/line, ReadLine {} cycles/line, {}.{} times as fast",
+                chunk,
+                cycles_per_line(naive),
+                cycles_per_line(read_line),
+                naive / read_line.max(1),
+                naive * 10 / read_line.max(1) % 10,
+            );
+        }
+        Timer::after_secs(5).await;
+    }
+}
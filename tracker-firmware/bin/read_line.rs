@@ -9,6 +9,8 @@ use embassy_rp::{
     peripherals::UART0,
     uart::{self, BufferedInterruptHandler, BufferedUart},
 };
+use embassy_time::Delay;
+use lines_codec::Timeouts;
 use static_cell::StaticCell;
 use {defmt_rtt as _, panic_probe as _};
 
@@ -18,6 +20,9 @@ bind_interrupts!(struct Irqs {
 
 const BUFFER_SIZE: usize = 256;
 
+/// The GPS sends once a second, and bytes within a line arrive about 1 ms apart at 9600 baud.
+const TIMEOUTS: Timeouts = Timeouts::new(2000, 50);
+
 #[embassy_executor::main]
 async fn main(_spawner: Spawner) -> ! {
     let config = embassy_rp::config::Config::default();
@@ -37,7 +42,7 @@ async fn main(_spawner: Spawner) -> ! {
     let mut line = [0u8; BUFFER_SIZE];
     loop {
         let Ok(len) = reader
-            .read_line_async(&mut line)
+            .read_line_timeout_async(&mut line, &mut Delay, TIMEOUTS)
             .await
             .inspect_err(|e| defmt::warn!("{}", e))
         else {
@@ -1,8 +1,6 @@
 #![no_std]
 #![no_main]
 
-use core::str::from_utf8;
-
 use defmt::{dbg, println};
 use embassy_executor::Spawner;
 use embassy_rp::{
@@ -10,6 +8,7 @@ use embassy_rp::{
     peripherals::UART0,
     uart::{self, BufferedInterruptHandler, BufferedUart},
 };
+use lines_codec::{Delimiter, LineReader};
 use static_cell::StaticCell;
 use {defmt_rtt as _, panic_probe as _};
 
@@ -19,6 +18,9 @@ bind_interrupts!(struct Irqs {
 
 const BUFFER_SIZE: usize = 256;
 
+/// Log reader statistics every this many lines, to size `BUFFER_SIZE` by.
+const STATS_INTERVAL: u32 = 100;
+
 #[embassy_executor::main]
 async fn main(_spawner: Spawner) -> ! {
     let config = embassy_rp::config::Config::default();
@@ -33,24 +35,37 @@ async fn main(_spawner: Spawner) -> ! {
     config.baudrate = 9600;
     let uart = BufferedUart::new(p.UART0, Irqs, p.PIN_0, p.PIN_1, tx_buf, rx_buf, config);
 
-    let mut reader = lines_codec::ReadLine::<_, BUFFER_SIZE>::new(uart);
+    static LINE_BUF: StaticCell<[u8; BUFFER_SIZE]> = StaticCell::new();
+    let line_buf = &mut LINE_BUF.init([0; BUFFER_SIZE])[..];
+
+    let mut reader = LineReader::from_storage(uart, line_buf)
+        .with_delimiter(Delimiter::CRLF)
+        .with_strip_delimiter(true)
+        .with_skip_empty(true);
 
     let mut nmea = tiny_nmea::NMEA::new();
 
-    let mut line = [0u8; BUFFER_SIZE];
     loop {
-        let Ok(len) = reader
-            .read_line_async(&mut line)
+        let Ok(line) = reader
+            .next_line_str_async()
             .await
             .inspect_err(|e| defmt::warn!("{}", e))
         else {
             continue;
         };
-        defmt::trace!("{}", from_utf8(&line[..len]).ok());
+        defmt::trace!("{}", line);
 
-        let s = line[..len].iter().map(|c| *c as char).collect();
+        let Ok(s) = heapless::String::try_from(line) else {
+            dbg!("line too long for the parser");
+            continue;
+        };
         let _ = nmea.update(&s).map_err(|()| dbg!("parser error"));
 
         println!("{:?}", nmea);
+
+        let stats = reader.stats();
+        if stats.lines % STATS_INTERVAL == 0 {
+            defmt::info!("{}", stats);
+        }
     }
 }
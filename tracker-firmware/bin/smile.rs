@@ -19,13 +19,15 @@ use smart_leds::{
 use {defmt_rtt as _, panic_probe as _};
 
 use tracker_firmware::adjust_color_for_led_type;
-use tracker_mapper::{index_of, Coordinate};
+use tracker_mapper::{polar_sprite, PolarCanvas, Sprite};
 
 bind_interrupts!(struct Irqs {
     PIO0_IRQ_0 => InterruptHandler<PIO0>;
 });
 
-const NUM_LEDS: usize = 57;
+const NOSE: u8 = b'n';
+const MOUTH: u8 = b'm';
+const EYES: u8 = b'e';
 
 #[embassy_executor::main]
 async fn main(_spawner: Spawner) -> ! {
@@ -39,68 +41,47 @@ async fn main(_spawner: Spawner) -> ! {
     let ws2812_program = PioWs2812Program::new(&mut common);
     let mut led_strip = PioWs2812::new(&mut common, sm0, p.DMA_CH0, p.PIN_16, &ws2812_program);
 
-    let mut leds = [RGB8::default(); NUM_LEDS];
+    let mut canvas = PolarCanvas::new();
 
     let mut rng = RoscRng;
 
-    let nose = [
-        Coordinate::new(0, 0),
-        Coordinate::new(1, 8),
-        Coordinate::new(1, 6),
-        Coordinate::new(1, 10),
-    ];
-    let mouth = [
-        Coordinate::new(3, 5),
-        Coordinate::new(3, 6),
-        Coordinate::new(3, 7),
-        Coordinate::new(3, 8),
-        Coordinate::new(3, 9),
-        Coordinate::new(3, 10),
-        Coordinate::new(3, 11),
-    ];
-    let eyes = [
-        // left.
-        Coordinate::new(2, 15),
-        Coordinate::new(2, 14),
-        Coordinate::new(3, 14),
-        // right.
-        Coordinate::new(2, 2),
-        Coordinate::new(2, 3),
-        Coordinate::new(3, 3),
-    ];
+    // One row per ring from the centre outwards, one column per step.
+    let face = polar_sprite!(
+        "n..............."
+        "......n.n.n....."
+        "..ee..........ee"
+        "...e.mmmmmmm..e."
+    );
 
     let nose_color = adjust_color_for_led_type(YELLOW);
     let mouth_color = adjust_color_for_led_type(FIREBRICK);
     let eye_color = adjust_color_for_led_type(GAINSBORO);
 
-    paint(&mut leds, &nose, nose_color);
-    paint(&mut leds, &mouth, mouth_color);
-    paint(&mut leds, &eyes, eye_color);
+    paint(&mut canvas, &face, NOSE, nose_color);
+    paint(&mut canvas, &face, MOUTH, mouth_color);
+    paint(&mut canvas, &face, EYES, eye_color);
 
     let mut delay = Delay;
     loop {
-        led_strip.write(&leds).await;
+        led_strip.write(canvas.leds()).await;
         let eyes_open = gen_range(&mut rng, 5000, 8000);
         defmt::trace!("eyes open for {}ms", eyes_open);
         delay.delay_ms(eyes_open).await;
 
-        paint(&mut leds, &eyes, DARK_SLATE_GRAY / 2);
-        led_strip.write(&leds).await;
+        paint(&mut canvas, &face, EYES, DARK_SLATE_GRAY / 2);
+        led_strip.write(canvas.leds()).await;
 
         let eyes_closed = gen_range(&mut rng, 100, 800);
         defmt::trace!("eyes closing for {}ms", eyes_closed);
         delay.delay_ms(eyes_closed).await;
-        paint(&mut leds, &eyes, BLUE_VIOLET);
+        paint(&mut canvas, &face, EYES, BLUE_VIOLET);
     }
 }
 
-fn paint(leds: &mut [RGB8], coordinates: &[Coordinate], color: RGB8) {
-    for coord in coordinates {
-        let index = index_of(*coord) as usize;
-        let mut color = color;
-        color /= 9; // adjust brightness
-        leds[index] = color;
-    }
+fn paint<const N: usize>(canvas: &mut PolarCanvas, face: &Sprite<N>, key: u8, color: RGB8) {
+    let mut color = color;
+    color /= 9; // adjust brightness
+    face.draw(canvas, key, color);
 }
 
 /// Random number within some range.
@@ -18,7 +18,7 @@ use bno080::{
     interface::{i2c::ALTERNATE_ADDRESS, I2cInterface},
     wrapper::BNO080,
 };
-use lines_codec::ReadLine;
+use lines_codec::{Demux, Frame, NmeaCounters};
 use num_quaternion::Q32;
 
 use defmt::unwrap;
@@ -49,7 +49,7 @@ use static_cell::StaticCell;
 use tiny_nmea::NMEA;
 
 use tracker_firmware::adjust_color_for_led_type;
-use tracker_mapper::{index_of, Coordinate};
+use tracker_mapper::{index_of, Coordinate, Orientation};
 
 bind_interrupts!(struct Irqs {
     PIO0_IRQ_0 => InterruptHandler<PIO0>;
@@ -66,6 +66,22 @@ const BNO_UPDATE_PERIOD: Duration = Duration::from_millis(10);
 
 const UART_BUFFER_SIZE: usize = 256;
 
+/// Log GPS reader statistics every this many frames, to size `UART_BUFFER_SIZE` by.
+const STATS_INTERVAL: u32 = 100;
+
+const UBX_CLASS_NAV: u8 = 0x01;
+const UBX_ID_PVT: u8 = 0x07;
+
+/// UBX-CFG-MSG: send NAV-PVT on this port with every navigation solution.
+///
+/// The receiver only sends NMEA by default.
+const ENABLE_NAV_PVT: [u8; 11] = [
+    0xb5, 0x62, 0x06, 0x01, 0x03, 0x00, UBX_CLASS_NAV, UBX_ID_PVT, 0x01, 0x13, 0x51,
+];
+
+/// How the PCB is mounted in the enclosure.
+const ORIENTATION: Orientation = Orientation::new(0, false);
+
 static STEP: Signal<CriticalSectionRawMutex, u8> = Signal::new();
 static NMEA: Signal<CriticalSectionRawMutex, NMEA> = Signal::new();
 
@@ -121,9 +137,14 @@ fn main() -> ! {
     config.baudrate = 9600;
     let tx = p.PIN_0;
     let rx = p.PIN_1;
-    let uart = BufferedUart::new(p.UART0, Irqs, tx, rx, tx_buf, rx_buf, config);
+    let mut uart = BufferedUart::new(p.UART0, Irqs, tx, rx, tx_buf, rx_buf, config);
+    if let Err(e) = embedded_io::Write::write_all(&mut uart, &ENABLE_NAV_PVT) {
+        defmt::warn!("could not enable NAV-PVT: {}", e);
+    }
 
-    let reader = lines_codec::ReadLine::<_, UART_BUFFER_SIZE>::new(uart);
+    static FRAME_BUF: StaticCell<[u8; UART_BUFFER_SIZE]> = StaticCell::new();
+    let frame_buf = &mut FRAME_BUF.init([0; UART_BUFFER_SIZE])[..];
+    let reader = Demux::from_storage(uart, frame_buf);
 
     // Core 0 runs GPS and main loop with LED update logic.
     let executor0 = EXECUTOR0.init(Executor::new());
@@ -146,7 +167,7 @@ async fn main_task(
         let next = select::select(STEP.wait(), NMEA.wait()).await;
         match next {
             Either::First(step) => {
-                let coord = Coordinate::new(3, step);
+                let coord = ORIENTATION.to_pcb(Coordinate::new(3, step));
                 // calculate led strip index
                 let index = index_of(coord);
                 // clear
@@ -164,31 +185,91 @@ async fn main_task(
 }
 
 #[embassy_executor::task]
-async fn monitor_gps(mut reader: ReadLine<BufferedUart<'static, UART0>, UART_BUFFER_SIZE>) {
+async fn monitor_gps(mut reader: Demux<BufferedUart<'static, UART0>, &'static mut [u8]>) {
     let mut nmea = tiny_nmea::NMEA::new();
-    let mut line = [0u8; UART_BUFFER_SIZE];
+    let mut counters = NmeaCounters::default();
+    let mut last_logged = 0;
 
     loop {
-        let Ok(bytes_read) = reader
-            .read_line_async(&mut line)
-            .await
-            .inspect_err(|e| defmt::warn!("{}", e))
-        else {
-            continue;
+        let stats = reader.stats();
+        if stats.lines / STATS_INTERVAL > last_logged {
+            last_logged = stats.lines / STATS_INTERVAL;
+            defmt::info!("{} {}", stats, counters);
+        }
+
+        let frame = match reader.next_frame_async().await {
+            Ok(Some(frame)) => frame,
+            Err(e) if e.is_transient() => {
+                defmt::warn!("{}", e);
+                continue;
+            }
+            Ok(None) => {
+                defmt::error!("GPS link is dead: UART closed");
+                return;
+            }
+            Err(e) => {
+                defmt::error!("GPS link is dead: {}", e);
+                return;
+            }
         };
-        defmt::trace!("{}", core::str::from_utf8(&line[..bytes_read]).ok());
 
-        let s = line[..bytes_read].iter().map(|c| *c as char).collect();
-        let _ = nmea.update(&s).map_err(|()| defmt::warn!("parser error"));
+        let (lat, lon) = match frame {
+            Frame::Nmea(line) => {
+                let sentence = match counters.check(line) {
+                    Ok(sentence) => sentence,
+                    Err(e) => {
+                        defmt::warn!("{} ({})", e, counters);
+                        continue;
+                    }
+                };
+                defmt::trace!("{}", sentence.line());
+
+                let Ok(s) = heapless::String::try_from(sentence.line()) else {
+                    defmt::warn!("line too long for the parser");
+                    continue;
+                };
+                let _ = nmea.update(&s).map_err(|()| defmt::warn!("parser error"));
 
-        defmt::println!("{:?}", nmea);
+                defmt::println!("{:?}", nmea);
 
-        let Some(lat) = nmea.latitude else { continue };
-        let Some(lon) = nmea.longitude else { continue };
+                let Some(lat) = nmea.latitude else { continue };
+                let Some(lon) = nmea.longitude else { continue };
+                (lat, lon)
+            }
+            Frame::Ubx {
+                class: UBX_CLASS_NAV,
+                id: UBX_ID_PVT,
+                payload,
+            } => {
+                let Some((lat, lon)) = nav_pvt_position(payload) else {
+                    continue;
+                };
+                (lat, lon)
+            }
+            Frame::Ubx { class, id, .. } => {
+                defmt::trace!("UBX {:02x} {:02x}", class, id);
+                continue;
+            }
+        };
         let _vector = (lat - TARGET_LAT, lon - TARGET_LON);
     }
 }
 
+/// Latitude and longitude in degrees from a UBX NAV-PVT payload, if it has a fix.
+fn nav_pvt_position(payload: &[u8]) -> Option<(f32, f32)> {
+    let field = |offset: usize| -> Option<i32> {
+        let bytes = payload.get(offset..offset + 4)?;
+        Some(i32::from_le_bytes(bytes.try_into().ok()?))
+    };
+    let fix_ok = payload.get(21)? & 0x01 != 0;
+    if !fix_ok {
+        return None;
+    }
+    let lon = field(24)? as f32 * 1e-7;
+    let lat = field(28)? as f32 * 1e-7;
+    Some((lat, lon))
+}
+
 #[embassy_executor::task]
 async fn monitor_bno(mut imu: BNO080<I2cInterface<I2c<'static, I2C0, i2c::Async>>>) {
     defmt::println!("monitoring bno080");
@@ -0,0 +1,122 @@
+use crate::{RingLayout, LAYOUT, RING_COUNT, STEP_ANGLE};
+
+/// LEDs and their share of the brightness for a point between the grid cells.
+///
+/// Weights are in `0.0..=1.0` and add up to 1. Coordinates that land on the same
+/// folded LED are merged into one entry.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Blend {
+    spots: [(u8, f32); 4],
+    len: usize,
+}
+
+impl Blend {
+    pub fn iter(&self) -> impl Iterator<Item = (u8, f32)> + '_ {
+        self.spots[..self.len].iter().copied()
+    }
+
+    fn add(&mut self, index: u8, weight: f32) {
+        if weight <= 0.0 {
+            return;
+        }
+        if let Some(spot) = self.spots[..self.len].iter_mut().find(|(i, _)| *i == index) {
+            spot.1 += weight;
+            return;
+        }
+        self.spots[self.len] = (index, weight);
+        self.len += 1;
+    }
+}
+
+/// Spread a fractional distance (in rings) and angle (in degrees) across the
+/// two nearest rings and the two nearest LEDs on each of them.
+///
+/// On rings with fewer LEDs than steps the angle is interpolated between the
+/// LEDs that are actually there, so the folded ring 1 blends in 45° spans and
+/// the centre takes whatever weight falls on ring 0.
+pub fn blend(distance: f32, angle: f32) -> Blend {
+    let distance = distance.clamp(0.0, (RING_COUNT - 1) as f32);
+    let angle = wrap_degrees(angle);
+
+    let inner = distance as u8;
+    let outer = (inner + 1).min(RING_COUNT as u8 - 1);
+    let outer_weight = distance - inner as f32;
+
+    let mut blend = Blend::default();
+    blend_ring(&mut blend, LAYOUT.ring(inner), angle, 1.0 - outer_weight);
+    blend_ring(&mut blend, LAYOUT.ring(outer), angle, outer_weight);
+    blend
+}
+
+/// `angle.rem_euclid(360.0)`, which `core` does not have.
+fn wrap_degrees(angle: f32) -> f32 {
+    let angle = angle % 360.0;
+    if angle < 0.0 {
+        angle + 360.0
+    } else {
+        angle
+    }
+}
+
+fn blend_ring(blend: &mut Blend, layout: RingLayout, angle: f32, weight: f32) {
+    let angle = wrap_degrees(angle - layout.offset as f32 * STEP_ANGLE);
+    let slot = angle * layout.led_count as f32 / 360.0;
+    let first = slot as u8;
+    let second_weight = slot - first as f32;
+
+    blend.add(layout.index_at(first), weight * (1.0 - second_weight));
+    blend.add(layout.index_at(first + 1), weight * second_weight);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Direction;
+    use test_case::test_case;
+
+    #[test_case(0.0, 123.0 => vec![(56, 1.0)])]
+    #[test_case(-1.0, 0.0 => vec![(56, 1.0)])]
+    #[test_case(0.5, 0.0 => vec![(56, 0.5), (55, 0.5)])]
+    #[test_case(1.0, 22.5 => vec![(55, 0.5), (54, 0.5)])]
+    #[test_case(1.0, 33.75 => vec![(55, 0.25), (54, 0.75)])]
+    #[test_case(1.0, 350.0 => vec![(48, 0.222), (55, 0.778)])]
+    #[test_case(4.0, 0.0 => vec![(15, 1.0)])]
+    #[test_case(4.0, 11.25 => vec![(15, 0.5), (14, 0.5)])]
+    #[test_case(9.0, 360.0 => vec![(15, 1.0)])]
+    #[test_case(3.25, -22.5 => vec![(16, 0.75), (0, 0.25)])]
+    #[test_case(2.5, 11.25 => vec![(47, 0.25), (46, 0.25), (31, 0.25), (30, 0.25)])]
+    fn blends_between_leds(distance: f32, angle: f32) -> Vec<(u8, f32)> {
+        blend(distance, angle)
+            .iter()
+            .map(|(index, weight)| (index, (weight * 1000.0).round() / 1000.0))
+            .collect()
+    }
+
+    #[test_case(4, 20 ; "offset past a full turn")]
+    #[test_case(4, 36 ; "offset past two full turns")]
+    fn wraps_ring_offset(offset: u8, wrapped: u8) {
+        let ring = |offset| RingLayout::new(16, 0, Direction::Clockwise, offset);
+        let mut expected = Blend::default();
+        blend_ring(&mut expected, ring(offset), 10.0, 1.0);
+        let mut blend = Blend::default();
+        blend_ring(&mut blend, ring(wrapped), 10.0, 1.0);
+        assert_eq!(blend, expected);
+        assert_eq!(
+            blend.iter().map(|(index, _)| index).collect::<Vec<_>>(),
+            [12, 13]
+        );
+    }
+
+    #[test]
+    fn weights_add_up_to_one() {
+        for distance in 0..=40 {
+            for angle in 0..360 {
+                let total: f32 = blend(distance as f32 / 10.0, angle as f32)
+                    .iter()
+                    .map(|(_, weight)| weight)
+                    .sum();
+                assert!((total - 1.0).abs() < 1e-4, "{distance} {angle}: {total}");
+            }
+        }
+    }
+}
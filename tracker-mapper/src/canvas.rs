@@ -0,0 +1,177 @@
+use core::ops::RangeInclusive;
+
+use smart_leds::RGB8;
+
+use crate::{index_of, Coordinate, Led, LEDS, NUM_LEDS, RING_COUNT, STEP_COUNT};
+
+/// A frame for the whole faceplate, drawn on by coordinate.
+///
+/// Drawing a coordinate colours the whole LED it lands on. Where several
+/// coordinates share a folded LED, the one drawn last wins, so
+/// `draw_arc(1, 1, 2, ..)` lights the two LEDs at steps 0 and 2 on ring 1
+/// and anything drawn on ring 0 recolours the centre LED.
+///
+/// Rings beyond the faceplate are ignored by every drawing method, and
+/// [`get`](Self::get) returns `None` for them. Steps wrap around the ring.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolarCanvas {
+    leds: [RGB8; NUM_LEDS],
+}
+
+impl Default for PolarCanvas {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PolarCanvas {
+    pub const fn new() -> Self {
+        Self {
+            leds: [RGB8::new(0, 0, 0); NUM_LEDS],
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.fill(RGB8::default());
+    }
+
+    pub fn fill(&mut self, color: RGB8) {
+        self.leds.iter_mut().for_each(|led| *led = color);
+    }
+
+    pub fn get(&self, coordinate: Coordinate) -> Option<RGB8> {
+        on_faceplate(coordinate.ring()).then(|| self.leds[index_of(coordinate) as usize])
+    }
+
+    pub fn set(&mut self, coordinate: Coordinate, color: RGB8) {
+        if on_faceplate(coordinate.ring()) {
+            self.leds[index_of(coordinate) as usize] = color;
+        }
+    }
+
+    pub fn fill_ring(&mut self, ring: u8, color: RGB8) {
+        self.draw_arc(ring, 0, STEP_COUNT as u8 - 1, color);
+    }
+
+    /// Colour `ring` clockwise from step `from` to step `to`, both included.
+    ///
+    /// The arc wraps past step 0 when `to` is before `from`.
+    pub fn draw_arc(&mut self, ring: u8, from: u8, to: u8, color: RGB8) {
+        let from = from % STEP_COUNT as u8;
+        let length = (to as usize + STEP_COUNT - from as usize) % STEP_COUNT + 1;
+        for step in (from as usize..).take(length) {
+            self.set(Coordinate::new(ring, (step % STEP_COUNT) as u8), color);
+        }
+    }
+
+    /// Colour `step` on every ring in `rings`.
+    pub fn draw_spoke(&mut self, step: u8, rings: RangeInclusive<u8>, color: RGB8) {
+        for ring in rings.filter(|ring| on_faceplate(*ring)) {
+            self.set(Coordinate::new(ring, step), color);
+        }
+    }
+
+    /// Colour the arc from `from` to `to` on every ring in `rings`.
+    pub fn draw_sector(&mut self, from: u8, to: u8, rings: RangeInclusive<u8>, color: RGB8) {
+        for ring in rings.filter(|ring| on_faceplate(*ring)) {
+            self.draw_arc(ring, from, to, color);
+        }
+    }
+
+    /// Every LED with its colour, in strip order.
+    pub fn iter(&self) -> impl Iterator<Item = (Led, RGB8)> + '_ {
+        LEDS.iter().copied().zip(self.leds.iter().copied())
+    }
+
+    /// The frame in strip order, ready to be written out.
+    pub fn leds(&self) -> &[RGB8; NUM_LEDS] {
+        &self.leds
+    }
+
+    pub fn leds_mut(&mut self) -> &mut [RGB8; NUM_LEDS] {
+        &mut self.leds
+    }
+}
+
+fn on_faceplate(ring: u8) -> bool {
+    (ring as usize) < RING_COUNT
+}
+
+impl AsRef<[RGB8]> for PolarCanvas {
+    fn as_ref(&self) -> &[RGB8] {
+        &self.leds
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use smart_leds::colors::{BLACK, RED, WHITE};
+    use test_case::test_case;
+
+    fn lit(canvas: &PolarCanvas) -> Vec<u8> {
+        canvas
+            .iter()
+            .filter(|(_, color)| *color != BLACK)
+            .map(|(led, _)| led.index())
+            .collect()
+    }
+
+    #[test_case(0 => vec![56])]
+    #[test_case(1 => vec![48, 49, 50, 51, 52, 53, 54, 55])]
+    #[test_case(4 => (0..16).collect::<Vec<_>>())]
+    fn fills_ring(ring: u8) -> Vec<u8> {
+        let mut canvas = PolarCanvas::new();
+        canvas.fill_ring(ring, RED);
+        lit(&canvas)
+    }
+
+    #[test_case(4, 0, 2 => vec![13, 14, 15])]
+    #[test_case(4, 14, 1 => vec![0, 1, 14, 15])]
+    #[test_case(4, 5, 5 => vec![10])]
+    #[test_case(1, 1, 2 => vec![54, 55])]
+    #[test_case(0, 3, 9 => vec![56])]
+    fn draws_arc(ring: u8, from: u8, to: u8) -> Vec<u8> {
+        let mut canvas = PolarCanvas::new();
+        canvas.draw_arc(ring, from, to, RED);
+        lit(&canvas)
+    }
+
+    #[test_case(0, 0..=4 => vec![15, 31, 47, 55, 56])]
+    #[test_case(3, 2..=9 => vec![12, 28, 44])]
+    fn draws_spoke(step: u8, rings: RangeInclusive<u8>) -> Vec<u8> {
+        let mut canvas = PolarCanvas::new();
+        canvas.draw_spoke(step, rings, RED);
+        lit(&canvas)
+    }
+
+    #[test]
+    fn draws_sector() {
+        let mut canvas = PolarCanvas::new();
+        canvas.draw_sector(15, 0, 3..=4, RED);
+        assert_eq!(lit(&canvas), [0, 15, 16, 31]);
+    }
+
+    #[test]
+    fn ignores_rings_beyond_faceplate() {
+        let mut canvas = PolarCanvas::new();
+        canvas.set(Coordinate::new(5, 0), RED);
+        canvas.fill_ring(5, RED);
+        canvas.draw_arc(9, 0, 3, RED);
+        canvas.draw_spoke(0, 5..=9, RED);
+        canvas.draw_sector(0, 3, 5..=9, RED);
+        assert!(lit(&canvas).is_empty());
+        assert_eq!(canvas.get(Coordinate::new(5, 0)), None);
+    }
+
+    #[test]
+    fn last_write_wins_on_shared_led() {
+        let mut canvas = PolarCanvas::new();
+        canvas.set(Coordinate::new(1, 0), RED);
+        canvas.set(Coordinate::new(1, 1), WHITE);
+        assert_eq!(canvas.get(Coordinate::new(1, 0)), Some(WHITE));
+
+        canvas.clear();
+        assert!(lit(&canvas).is_empty());
+    }
+}
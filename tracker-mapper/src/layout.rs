@@ -0,0 +1,258 @@
+use crate::{Coordinate, Led, STEP_COUNT};
+
+/// Which way the strip index grows along a ring, seen from the LED side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Clockwise,
+    CounterClockwise,
+}
+
+/// How the LEDs of one ring are placed and chained.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RingLayout {
+    /// Number of LEDs on the ring, at most [`STEP_COUNT`].
+    pub led_count: u8,
+    /// Strip index of the first LED of the ring.
+    pub first_index: u8,
+    /// Which way the following LEDs go from the first one.
+    pub direction: Direction,
+    /// Step at which the first LED sits.
+    pub offset: u8,
+}
+
+impl RingLayout {
+    pub const fn new(led_count: u8, first_index: u8, direction: Direction, offset: u8) -> Self {
+        Self {
+            led_count,
+            first_index,
+            direction,
+            offset,
+        }
+    }
+
+    /// Strip index of the LED in `slot`, counting clockwise from the first LED.
+    pub(crate) const fn index_at(&self, slot: u8) -> u8 {
+        let slot = slot % self.led_count;
+        let led = match self.direction {
+            Direction::Clockwise => slot,
+            Direction::CounterClockwise => (self.led_count - slot) % self.led_count,
+        };
+        self.first_index + led
+    }
+
+    /// Strip index of the LED that covers `step`.
+    ///
+    /// Each LED covers the steps from where it sits up to the next LED clockwise.
+    const fn index_of(&self, step: u8) -> u8 {
+        let step = step as usize % STEP_COUNT;
+        let offset = self.offset as usize % STEP_COUNT;
+        let clockwise = (step + STEP_COUNT - offset) % STEP_COUNT;
+        let slot = clockwise * self.led_count as usize / STEP_COUNT;
+        self.index_at(slot as u8)
+    }
+}
+
+/// The LED arrangement of one faceplate, from the centre ring outwards.
+///
+/// Everything else in this crate is generated from [`LAYOUT`](crate::LAYOUT),
+/// so a new PCB revision only needs a new layout constant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Layout<const RINGS: usize> {
+    rings: [RingLayout; RINGS],
+}
+
+impl<const RINGS: usize> Layout<RINGS> {
+    pub const fn new(rings: [RingLayout; RINGS]) -> Self {
+        let mut ring = 0;
+        while ring < RINGS {
+            assert!(rings[ring].led_count > 0, "ring without LEDs");
+            // Every LED needs at least one step to be reachable.
+            assert!(
+                rings[ring].led_count as usize <= STEP_COUNT,
+                "more LEDs than steps on a ring"
+            );
+            ring += 1;
+        }
+        Self { rings }
+    }
+
+    pub const fn ring(&self, ring: u8) -> RingLayout {
+        self.rings[ring as usize]
+    }
+
+    /// Total number of LEDs on the strip.
+    pub const fn led_count(&self) -> usize {
+        let mut count = 0;
+        let mut ring = 0;
+        while ring < RINGS {
+            count += self.rings[ring].led_count as usize;
+            ring += 1;
+        }
+        count
+    }
+
+    /// Strip index of the LED at `coordinate`. Steps wrap around the ring.
+    ///
+    /// # Panics
+    ///
+    /// If `coordinate` is on a ring beyond the layout, see [`Coordinate::try_new`].
+    pub const fn index_of(&self, Coordinate { ring, step }: Coordinate) -> u8 {
+        assert!((ring as usize) < RINGS, "ring out of range");
+        self.rings[ring as usize].index_of(step)
+    }
+
+    /// Strip index for every ring and step.
+    pub const fn table(&self) -> [[u8; STEP_COUNT]; RINGS] {
+        let mut table = [[0u8; STEP_COUNT]; RINGS];
+        let mut ring = 0;
+        while ring < RINGS {
+            let mut step = 0;
+            while step < STEP_COUNT {
+                table[ring][step] = self.index_of(Coordinate::new(ring as u8, step as u8));
+                step += 1;
+            }
+            ring += 1;
+        }
+        table
+    }
+
+    /// Every LED on the strip, by index.
+    ///
+    /// `LEDS` must equal [`Layout::led_count`], and every index must be
+    /// claimed by exactly one ring.
+    pub const fn leds<const LEDS: usize>(&self) -> [Led; LEDS] {
+        assert!(LEDS == self.led_count(), "LED count does not match layout");
+        let mut leds = [Led {
+            index: 0,
+            ring: 0,
+            first_step: 0,
+            step_count: 0,
+        }; LEDS];
+        let mut claimed = [false; LEDS];
+        let mut ring = 0;
+        while ring < RINGS {
+            let layout = self.rings[ring];
+            let mut led = 0;
+            while led < layout.led_count {
+                let index = layout.first_index + led;
+                assert!((index as usize) < LEDS, "LED index beyond strip");
+                assert!(!claimed[index as usize], "LED index used twice");
+                claimed[index as usize] = true;
+                leds[index as usize].index = index;
+                leds[index as usize].ring = ring as u8;
+                led += 1;
+            }
+            ring += 1;
+        }
+
+        let mut ring = 0;
+        while ring < RINGS {
+            // Walk clockwise from the ring's offset so that the first covered
+            // step of each LED is seen first, even when its steps wrap past 0.
+            let offset = self.rings[ring].offset as usize;
+            let mut walked = 0;
+            while walked < STEP_COUNT {
+                let step = ((offset + walked) % STEP_COUNT) as u8;
+                let index = self.index_of(Coordinate::new(ring as u8, step)) as usize;
+                if leds[index].step_count == 0 {
+                    leds[index].first_step = step;
+                }
+                leds[index].step_count += 1;
+                walked += 1;
+            }
+            ring += 1;
+        }
+        leds
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_case::test_case;
+
+    const OUTER_12: Layout<2> = Layout::new([
+        RingLayout::new(1, 12, Direction::Clockwise, 0),
+        RingLayout::new(12, 0, Direction::Clockwise, 0),
+    ]);
+
+    const ROTATED: Layout<2> = Layout::new([
+        RingLayout::new(1, 0, Direction::Clockwise, 0),
+        RingLayout::new(8, 1, Direction::Clockwise, 3),
+    ]);
+
+    const QUARTERS: Layout<1> = Layout::new([RingLayout::new(4, 0, Direction::Clockwise, 0)]);
+
+    #[test_case(Coordinate::new(0, 7) => 12)]
+    #[test_case(Coordinate::new(1, 0) => 0)]
+    #[test_case(Coordinate::new(1, 1) => 0)]
+    #[test_case(Coordinate::new(1, 2) => 1)]
+    #[test_case(Coordinate::new(1, 5) => 3)]
+    #[test_case(Coordinate::new(1, 15) => 11)]
+    fn maps_outer_ring_of_12(coordinate: Coordinate) -> u8 {
+        OUTER_12.index_of(coordinate)
+    }
+
+    #[test_case(Coordinate::new(1, 3) => 1)]
+    #[test_case(Coordinate::new(1, 4) => 1)]
+    #[test_case(Coordinate::new(1, 5) => 2)]
+    #[test_case(Coordinate::new(1, 2) => 8)]
+    #[test_case(Coordinate::new(1, 0) => 7)]
+    fn maps_ring_with_offset(coordinate: Coordinate) -> u8 {
+        ROTATED.index_of(coordinate)
+    }
+
+    #[test]
+    fn wraps_led_coverage_past_step_zero() {
+        let leds = ROTATED.leds::<9>();
+        let steps: Vec<u8> = leds[7].coordinates().map(|c| c.step()).collect();
+        assert_eq!(steps, [15, 0]);
+        assert!(leds[7].covers(Coordinate::new(1, 0)));
+        assert!(!leds[7].covers(Coordinate::new(1, 1)));
+    }
+
+    #[test_case(ROTATED.leds::<9>()[0] => 0.0 ; "centre")]
+    #[test_case(ROTATED.leds::<9>()[1] => 67.5 ; "two steps")]
+    #[test_case(ROTATED.leds::<9>()[7] => 337.5 ; "two steps past zero")]
+    #[test_case(QUARTERS.leds::<4>()[1] => 90.0 ; "four steps")]
+    #[test_case(Led::default() => 0.0 ; "no steps")]
+    fn finds_angle_of_first_step(led: Led) -> f32 {
+        led.angle()
+    }
+
+    #[test]
+    fn counts_leds() {
+        assert_eq!(OUTER_12.led_count(), 13);
+        assert_eq!(ROTATED.led_count(), 9);
+    }
+
+    #[test]
+    #[should_panic = "ring out of range"]
+    fn rejects_ring_beyond_layout() {
+        ROTATED.index_of(Coordinate::new(2, 0));
+    }
+
+    #[test]
+    fn every_led_covers_a_step() {
+        assert!(OUTER_12
+            .leds::<13>()
+            .iter()
+            .all(|led| led.coordinates().count() > 0));
+    }
+
+    #[test]
+    #[should_panic = "more LEDs than steps on a ring"]
+    fn rejects_ring_with_more_leds_than_steps() {
+        Layout::new([RingLayout::new(24, 0, Direction::Clockwise, 0)]);
+    }
+
+    #[test]
+    #[should_panic = "LED index used twice"]
+    fn rejects_overlapping_rings() {
+        Layout::new([
+            RingLayout::new(4, 0, Direction::Clockwise, 0),
+            RingLayout::new(4, 2, Direction::Clockwise, 0),
+        ])
+        .leds::<8>();
+    }
+}
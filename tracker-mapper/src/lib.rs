@@ -1,4 +1,26 @@
-#![cfg_attr(not(test), no_std)]
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
+
+pub use blend::{blend, Blend};
+#[cfg(feature = "smart-leds")]
+pub use canvas::PolarCanvas;
+pub use layout::{Direction, Layout, RingLayout};
+pub use position::{nearest_led, Position, POSITIONS, RING_PITCH_MM};
+pub use range::{LinearScale, LogScale, RangeRing, RangeScale, ThresholdScale};
+pub use sprite::{cell_count as sprite_cell_count, Sprite, SpriteCell};
+#[cfg(feature = "svg")]
+pub use svg::render_svg;
+pub use transform::{mirror_frame, rotate_frame, Orientation};
+
+mod blend;
+#[cfg(feature = "smart-leds")]
+mod canvas;
+mod layout;
+mod position;
+mod range;
+mod sprite;
+#[cfg(feature = "svg")]
+mod svg;
+mod transform;
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Coordinate {
@@ -6,15 +28,78 @@ pub struct Coordinate {
     step: u8,
 }
 
-pub const NUM_LEDS: usize = 57;
+/// The faceplate this crate maps onto: a centre LED, a folded ring of 8
+/// and three rings of 16, chained from the outer ring inwards.
+pub const LAYOUT: Layout<RING_COUNT> = Layout::new([
+    RingLayout::new(1, 56, Direction::CounterClockwise, 0),
+    RingLayout::new(8, 48, Direction::CounterClockwise, 14),
+    RingLayout::new(16, 32, Direction::CounterClockwise, 15),
+    RingLayout::new(16, 16, Direction::CounterClockwise, 15),
+    RingLayout::new(16, 0, Direction::CounterClockwise, 15),
+]);
+
+pub const NUM_LEDS: usize = LAYOUT.led_count();
+
+pub static ARRAY: [[u8; STEP_COUNT]; RING_COUNT] = LAYOUT.table();
+
+pub static LEDS: [Led; NUM_LEDS] = LAYOUT.leds();
+
+/// Why a ring and step do not make a [`Coordinate`] on this faceplate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoordinateError {
+    RingOutOfRange(u8),
+    StepOutOfRange(u8),
+}
+
+impl core::fmt::Display for CoordinateError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::RingOutOfRange(ring) => write!(f, "ring {ring} is not below {RING_COUNT}"),
+            Self::StepOutOfRange(step) => write!(f, "step {step} is not below {STEP_COUNT}"),
+        }
+    }
+}
 
-pub static ARRAY: [[u8; STEP_COUNT]; RING_COUNT] = make_table();
+impl core::error::Error for CoordinateError {}
 
 impl Coordinate {
     pub const fn new(ring: u8, step: u8) -> Self {
         Self { ring, step }
     }
 
+    pub const fn try_new(ring: u8, step: u8) -> Result<Self, CoordinateError> {
+        if ring as usize >= RING_COUNT {
+            return Err(CoordinateError::RingOutOfRange(ring));
+        }
+        if step as usize >= STEP_COUNT {
+            return Err(CoordinateError::StepOutOfRange(step));
+        }
+        Ok(Self { ring, step })
+    }
+
+    pub const fn ring(&self) -> u8 {
+        self.ring
+    }
+
+    pub const fn step(&self) -> u8 {
+        self.step
+    }
+
+    /// Every coordinate on the faceplate, ring by ring from the centre outwards.
+    pub fn all() -> impl Iterator<Item = Self> {
+        (0..RING_COUNT as u8).flat_map(Self::on_ring)
+    }
+
+    /// Every step of `ring`, clockwise from step 0. Empty if there is no such ring.
+    pub fn on_ring(ring: u8) -> impl Iterator<Item = Self> {
+        let steps = if (ring as usize) < RING_COUNT {
+            0..STEP_COUNT as u8
+        } else {
+            0..0
+        };
+        steps.map(move |step| Self { ring, step })
+    }
+
     pub fn from_world_coordinates(distance: u8, angle: u16) -> Self {
         let ring = distance.min(4);
         let angle = angle % 360;
@@ -28,49 +113,66 @@ impl Coordinate {
 pub const RING_COUNT: usize = 5;
 pub const STEP_COUNT: usize = 16;
 
-const fn make_table() -> [[u8; STEP_COUNT]; RING_COUNT] {
-    let mut array: [[u8; STEP_COUNT]; RING_COUNT] = [[0u8; STEP_COUNT]; RING_COUNT];
-    let mut ring = 0;
-    let mut step = 0;
-    loop {
-        loop {
-            array[ring as usize][step as usize] = index_of(Coordinate::new(ring, step));
-            step += 1;
-            if step == STEP_COUNT as u8 {
-                break;
-            }
-        }
-        ring += 1;
-        step = 0;
-        if ring == RING_COUNT as u8 {
-            break;
+/// Angle between two neighbouring steps, in degrees.
+pub const STEP_ANGLE: f32 = 360.0 / STEP_COUNT as f32;
+
+/// A physical LED and the run of coordinates on its ring that land on it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Led {
+    index: u8,
+    ring: u8,
+    first_step: u8,
+    step_count: u8,
+}
+
+impl Led {
+    pub const fn index(&self) -> u8 {
+        self.index
+    }
+
+    pub const fn ring(&self) -> u8 {
+        self.ring
+    }
+
+    /// Angle in degrees, clockwise from step 0, at which the LED sits.
+    ///
+    /// A folded LED sits on the first step it covers, so the LEDs of ring 1
+    /// are at the even steps. The centre LED has no direction and reports 0,
+    /// as does an LED that covers no steps at all.
+    pub fn angle(&self) -> f32 {
+        if self.step_count == 0 || self.step_count as usize >= STEP_COUNT {
+            return 0.0;
         }
+        self.first_step as f32 * STEP_ANGLE
     }
-    array
-}
 
-pub const fn index_of(coordinate: Coordinate) -> u8 {
-    let virtual_index = virtual_index_of(coordinate);
-    devirtualize_led_index(virtual_index)
+    pub const fn covers(&self, coordinate: Coordinate) -> bool {
+        let distance =
+            (coordinate.step as usize + STEP_COUNT - self.first_step as usize) % STEP_COUNT;
+        coordinate.ring == self.ring && distance < self.step_count as usize
+    }
+
+    /// Every coordinate that maps onto this LED.
+    pub fn coordinates(&self) -> impl Iterator<Item = Coordinate> {
+        let ring = self.ring;
+        let first_step = self.first_step as usize;
+        (first_step..first_step + self.step_count as usize)
+            .map(move |step| Coordinate::new(ring, (step % STEP_COUNT) as u8))
+    }
 }
 
-const fn virtual_index_of(Coordinate { ring, step }: Coordinate) -> u8 {
-    let result = ring * 16 + step;
-    79 - result
+/// Inverse of [`index_of`]: where the LED at strip position `index` sits.
+pub fn coordinates_of(index: u8) -> Option<Led> {
+    LEDS.get(index as usize).copied()
 }
 
-const fn devirtualize_led_index(virtual_index: u8) -> u8 {
-    let virtual_index = virtual_index % 80;
-    match virtual_index {
-        0..48 => virtual_index,
-        48..64 => {
-            let index = virtual_index - 48;
-            let index = index / 2;
-            index + 48
-        }
-        64..80 => 56,
-        _ => panic!("a"),
-    }
+/// Strip index of the LED at `coordinate` on [`LAYOUT`], see [`Layout::index_of`].
+///
+/// # Panics
+///
+/// If the ring is not below [`RING_COUNT`].
+pub const fn index_of(coordinate: Coordinate) -> u8 {
+    LAYOUT.index_of(coordinate)
 }
 
 #[cfg(test)]
@@ -78,6 +180,53 @@ mod test {
     use super::*;
     use test_case::test_case;
 
+    /// Hand-written mapping of the first PCB revision, kept to check `LAYOUT` against.
+    const fn virtual_index_of(Coordinate { ring, step }: Coordinate) -> u8 {
+        let result = ring * 16 + step;
+        79 - result
+    }
+
+    const fn devirtualize_led_index(virtual_index: u8) -> u8 {
+        let virtual_index = virtual_index % 80;
+        match virtual_index {
+            0..48 => virtual_index,
+            48..64 => {
+                let index = virtual_index - 48;
+                let index = index / 2;
+                index + 48
+            }
+            64..80 => 56,
+            _ => panic!("a"),
+        }
+    }
+
+    #[test_case(0, 0 => Ok(Coordinate::new(0, 0)))]
+    #[test_case(4, 15 => Ok(Coordinate::new(4, 15)))]
+    #[test_case(5, 0 => Err(CoordinateError::RingOutOfRange(5)))]
+    #[test_case(9, 200 => Err(CoordinateError::RingOutOfRange(9)))]
+    #[test_case(2, 16 => Err(CoordinateError::StepOutOfRange(16)))]
+    fn validates_coordinate(ring: u8, step: u8) -> Result<Coordinate, CoordinateError> {
+        Coordinate::try_new(ring, step)
+    }
+
+    #[test]
+    fn enumerates_all_coordinates() {
+        let all: Vec<_> = Coordinate::all().collect();
+        assert_eq!(all.len(), RING_COUNT * STEP_COUNT);
+        assert_eq!(all.first(), Some(&Coordinate::new(0, 0)));
+        assert_eq!(all.last(), Some(&Coordinate::new(4, 15)));
+        assert!(all.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(all
+            .iter()
+            .all(|c| Coordinate::try_new(c.ring(), c.step()) == Ok(*c)));
+    }
+
+    #[test_case(2 => (0..16).map(|step| Coordinate::new(2, step)).collect::<Vec<_>>())]
+    #[test_case(5 => Vec::<Coordinate>::new())]
+    fn enumerates_ring(ring: u8) -> Vec<Coordinate> {
+        Coordinate::on_ring(ring).collect()
+    }
+
     #[test_case(0, 0 => Coordinate::new(0, 0))]
     #[test_case(1, 45 => Coordinate::new(1, 2))]
     #[test_case(4, 180 => Coordinate::new(4, 8))]
@@ -119,17 +268,69 @@ mod test {
 
     #[test]
     fn index_of_and_table_are_equivalent() {
-        let table = make_table();
+        let table = LAYOUT.table();
 
         dbg!(std::mem::size_of_val(&table));
 
-        for ring in 0..RING_COUNT {
-            for step in 0..STEP_COUNT {
+        for (ring, steps) in table.iter().enumerate() {
+            for (step, index) in steps.iter().enumerate() {
+                assert_eq!(*index, index_of(Coordinate::new(ring as u8, step as u8)));
+            }
+        }
+    }
+
+    #[test]
+    fn layout_matches_hand_written_mapping() {
+        for ring in 0..RING_COUNT as u8 {
+            for step in 0..STEP_COUNT as u8 {
+                let coordinate = Coordinate::new(ring, step);
                 assert_eq!(
-                    table[ring][step],
-                    index_of(Coordinate::new(ring as u8, step as u8))
+                    index_of(coordinate),
+                    devirtualize_led_index(virtual_index_of(coordinate))
                 );
             }
         }
     }
+
+    #[test_case(56 => (0, vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]))]
+    #[test_case(55 => (1, vec![0, 1]))]
+    #[test_case(54 => (1, vec![2, 3]))]
+    #[test_case(48 => (1, vec![14, 15]))]
+    #[test_case(47 => (2, vec![0]))]
+    #[test_case(16 => (3, vec![15]))]
+    #[test_case(15 => (4, vec![0]))]
+    #[test_case(0 => (4, vec![15]))]
+    fn finds_coordinates_of_led(index: u8) -> (u8, Vec<u8>) {
+        let led = coordinates_of(index).unwrap();
+        assert_eq!(led.index(), index);
+        (led.ring(), led.coordinates().map(|c| c.step()).collect())
+    }
+
+    #[test_case(56 => 0.0)]
+    #[test_case(55 => 0.0)]
+    #[test_case(54 => 45.0)]
+    #[test_case(48 => 315.0)]
+    #[test_case(44 => 67.5)]
+    #[test_case(0 => 337.5)]
+    fn finds_angle_of_led(index: u8) -> f32 {
+        coordinates_of(index).unwrap().angle()
+    }
+
+    #[test]
+    fn rejects_index_beyond_strip() {
+        assert_eq!(coordinates_of(NUM_LEDS as u8), None);
+    }
+
+    #[test]
+    fn coordinates_of_inverts_index_of() {
+        let mut covered = 0;
+        for led in &LEDS {
+            for coordinate in led.coordinates() {
+                assert!(led.covers(coordinate));
+                assert_eq!(index_of(coordinate), led.index());
+                covered += 1;
+            }
+        }
+        assert_eq!(covered, RING_COUNT * STEP_COUNT);
+    }
 }
@@ -0,0 +1,143 @@
+use core::f32::consts::FRAC_1_SQRT_2;
+
+use crate::{Coordinate, Led, LAYOUT, NUM_LEDS, STEP_COUNT};
+
+/// Distance between the centres of two neighbouring rings on the PCB, in millimetres.
+///
+/// Taken from the layout in `pcb.png`: the rings sit three grid squares apart,
+/// and one grid square matches the 2.54 mm pitch of the programming header.
+pub const RING_PITCH_MM: f32 = 7.62;
+
+/// A point on the faceplate in millimetres, seen from the LED side.
+///
+/// The origin is the centre LED, `x` grows to the right and `y` grows towards step 0.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Position {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// Physical position of every LED, by strip index.
+pub static POSITIONS: [Position; NUM_LEDS] = make_positions();
+
+const SIN_STEP: f32 = 0.38268343;
+const COS_STEP: f32 = 0.9238795;
+
+/// Unit vector pointing at each step, clockwise from step 0.
+const DIRECTIONS: [Position; STEP_COUNT] = [
+    Position::new(0.0, 1.0),
+    Position::new(SIN_STEP, COS_STEP),
+    Position::new(FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+    Position::new(COS_STEP, SIN_STEP),
+    Position::new(1.0, 0.0),
+    Position::new(COS_STEP, -SIN_STEP),
+    Position::new(FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+    Position::new(SIN_STEP, -COS_STEP),
+    Position::new(0.0, -1.0),
+    Position::new(-SIN_STEP, -COS_STEP),
+    Position::new(-FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+    Position::new(-COS_STEP, -SIN_STEP),
+    Position::new(-1.0, 0.0),
+    Position::new(-COS_STEP, SIN_STEP),
+    Position::new(-FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+    Position::new(-SIN_STEP, COS_STEP),
+];
+
+impl Position {
+    pub const fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+
+    pub fn distance_squared(&self, other: Position) -> f32 {
+        let dx = self.x - other.x;
+        let dy = self.y - other.y;
+        dx * dx + dy * dy
+    }
+}
+
+impl Coordinate {
+    /// Where this coordinate points on the faceplate.
+    ///
+    /// Coordinates that share a folded LED point at different spots,
+    /// use [`Led::position`] for where the LED itself sits.
+    pub const fn to_xy(self) -> Position {
+        let radius = self.ring as f32 * RING_PITCH_MM;
+        let direction = DIRECTIONS[self.step as usize % STEP_COUNT];
+        Position::new(direction.x * radius, direction.y * radius)
+    }
+}
+
+impl Led {
+    pub const fn position(&self) -> Position {
+        Coordinate::new(self.ring, self.first_step).to_xy()
+    }
+}
+
+/// Strip index of the LED closest to `position`.
+pub fn nearest_led(position: Position) -> u8 {
+    let mut nearest = 0;
+    let mut nearest_distance = f32::INFINITY;
+    for (index, led) in POSITIONS.iter().enumerate() {
+        let distance = led.distance_squared(position);
+        if distance < nearest_distance {
+            nearest = index as u8;
+            nearest_distance = distance;
+        }
+    }
+    nearest
+}
+
+const fn make_positions() -> [Position; NUM_LEDS] {
+    let leds = LAYOUT.leds::<NUM_LEDS>();
+    let mut positions = [Position::new(0.0, 0.0); NUM_LEDS];
+    let mut index = 0;
+    while index < NUM_LEDS {
+        positions[index] = leds[index].position();
+        index += 1;
+    }
+    positions
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{coordinates_of, index_of};
+    use test_case::test_case;
+
+    #[test_case(Coordinate::new(0, 5) => (0.0, 0.0))]
+    #[test_case(Coordinate::new(1, 0) => (0.0, 7.62))]
+    #[test_case(Coordinate::new(2, 4) => (15.24, 0.0))]
+    #[test_case(Coordinate::new(3, 8) => (0.0, -22.86))]
+    #[test_case(Coordinate::new(4, 12) => (-30.48, 0.0))]
+    fn converts_coordinate_to_xy(coordinate: Coordinate) -> (f32, f32) {
+        let Position { x, y } = coordinate.to_xy();
+        ((x * 1000.0).round() / 1000.0, (y * 1000.0).round() / 1000.0)
+    }
+
+    #[test_case(0.0, 0.0 => 56)]
+    #[test_case(2.0, -1.0 => 56)]
+    #[test_case(0.0, 7.5 => 55)]
+    #[test_case(0.0, 100.0 => 15)]
+    #[test_case(29.0, 1.0 => 11)]
+    #[test_case(-16.0, 16.0 => 17)]
+    fn finds_nearest_led(x: f32, y: f32) -> u8 {
+        nearest_led(Position::new(x, y))
+    }
+
+    #[test]
+    fn nearest_led_of_every_led_is_itself() {
+        for (index, position) in POSITIONS.iter().enumerate() {
+            assert_eq!(nearest_led(*position), index as u8);
+        }
+    }
+
+    #[test]
+    fn leds_sit_on_their_first_coordinate() {
+        for index in 0..NUM_LEDS as u8 {
+            let led = coordinates_of(index).unwrap();
+            let first = led.coordinates().next().unwrap();
+            assert_eq!(index_of(first), index);
+            assert_eq!(POSITIONS[index as usize], first.to_xy());
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use crate::RING_COUNT;
+
+/// Rings between the centre and the edge that show how far away the target is.
+const DISTANCE_RINGS: u8 = RING_COUNT as u8 - 1;
+
+/// Where a distance to the target lands on the faceplate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeRing {
+    /// Closer than the arrival radius, shown on the centre LED.
+    Arrived,
+    /// Somewhere in between, on ring 1 (near) up to the outer ring (far).
+    Ring(u8),
+    /// Farther than the scale reaches.
+    Beyond,
+}
+
+impl RangeRing {
+    /// The ring to draw on, with "beyond" pinned to the outer ring.
+    pub const fn ring(self) -> u8 {
+        match self {
+            Self::Arrived => 0,
+            Self::Ring(ring) => ring,
+            Self::Beyond => DISTANCE_RINGS,
+        }
+    }
+}
+
+/// Maps a distance in metres onto a ring.
+pub trait RangeScale {
+    fn ring_for(&self, metres: f32) -> RangeRing;
+}
+
+/// Rings of equal width between the arrival radius and the edge of the scale.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinearScale {
+    arrived: f32,
+    beyond: f32,
+}
+
+impl LinearScale {
+    pub const fn new(arrived: f32, beyond: f32) -> Self {
+        Self { arrived, beyond }
+    }
+}
+
+impl RangeScale for LinearScale {
+    fn ring_for(&self, metres: f32) -> RangeRing {
+        scale(metres, self.arrived, self.beyond, |metres| metres)
+    }
+}
+
+/// Every ring covers the same factor of distance, so near rings are narrow
+/// and far rings wide.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LogScale {
+    arrived: f32,
+    beyond: f32,
+}
+
+impl LogScale {
+    pub const fn new(arrived: f32, beyond: f32) -> Self {
+        Self { arrived, beyond }
+    }
+}
+
+impl RangeScale for LogScale {
+    fn ring_for(&self, metres: f32) -> RangeRing {
+        scale(metres, self.arrived, self.beyond, libm::logf)
+    }
+}
+
+/// Explicit upper bounds in metres, from the arrival radius out to the
+/// edge of the outer ring.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThresholdScale {
+    thresholds: [f32; RING_COUNT],
+}
+
+impl ThresholdScale {
+    pub const fn new(thresholds: [f32; RING_COUNT]) -> Self {
+        Self { thresholds }
+    }
+}
+
+impl RangeScale for ThresholdScale {
+    fn ring_for(&self, metres: f32) -> RangeRing {
+        match self.thresholds.iter().position(|limit| metres < *limit) {
+            Some(0) => RangeRing::Arrived,
+            Some(ring) => RangeRing::Ring(ring as u8),
+            None => RangeRing::Beyond,
+        }
+    }
+}
+
+fn scale(metres: f32, arrived: f32, beyond: f32, transform: impl Fn(f32) -> f32) -> RangeRing {
+    if metres < arrived {
+        return RangeRing::Arrived;
+    }
+    if metres >= beyond {
+        return RangeRing::Beyond;
+    }
+    let (metres, arrived, beyond) = (transform(metres), transform(arrived), transform(beyond));
+    let fraction = (metres - arrived) / (beyond - arrived);
+    let ring = (fraction * DISTANCE_RINGS as f32) as u8;
+    RangeRing::Ring(ring.min(DISTANCE_RINGS - 1) + 1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(0.0 => RangeRing::Arrived)]
+    #[test_case(9.9 => RangeRing::Arrived)]
+    #[test_case(10.0 => RangeRing::Ring(1))]
+    #[test_case(259.0 => RangeRing::Ring(1))]
+    #[test_case(261.0 => RangeRing::Ring(2))]
+    #[test_case(999.0 => RangeRing::Ring(4))]
+    #[test_case(1010.0 => RangeRing::Beyond)]
+    fn scales_linearly(metres: f32) -> RangeRing {
+        LinearScale::new(10.0, 1010.0).ring_for(metres)
+    }
+
+    #[test_case(5.0 => RangeRing::Arrived)]
+    #[test_case(10.0 => RangeRing::Ring(1))]
+    #[test_case(99.0 => RangeRing::Ring(1))]
+    #[test_case(101.0 => RangeRing::Ring(2))]
+    #[test_case(1001.0 => RangeRing::Ring(3))]
+    #[test_case(10_001.0 => RangeRing::Ring(4))]
+    #[test_case(100_000.0 => RangeRing::Beyond)]
+    fn scales_logarithmically(metres: f32) -> RangeRing {
+        LogScale::new(10.0, 100_000.0).ring_for(metres)
+    }
+
+    #[test_case(9.0 => RangeRing::Arrived)]
+    #[test_case(10.0 => RangeRing::Ring(1))]
+    #[test_case(150.0 => RangeRing::Ring(2))]
+    #[test_case(4999.0 => RangeRing::Ring(4))]
+    #[test_case(5000.0 => RangeRing::Beyond)]
+    fn scales_by_thresholds(metres: f32) -> RangeRing {
+        ThresholdScale::new([10.0, 100.0, 500.0, 1000.0, 5000.0]).ring_for(metres)
+    }
+
+    #[test_case(RangeRing::Arrived => 0)]
+    #[test_case(RangeRing::Ring(2) => 2)]
+    #[test_case(RangeRing::Beyond => 4)]
+    fn draws_range_on_ring(range: RangeRing) -> u8 {
+        range.ring()
+    }
+}
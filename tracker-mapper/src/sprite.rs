@@ -0,0 +1,209 @@
+use crate::{Coordinate, LAYOUT, RING_COUNT, STEP_COUNT};
+
+/// A lit LED of a [`Sprite`] and the colour key it was drawn with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SpriteCell {
+    pub index: u8,
+    pub key: u8,
+}
+
+/// LEDs picked out of a text diagram, see [`polar_sprite!`](crate::polar_sprite).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sprite<const N: usize> {
+    cells: [SpriteCell; N],
+}
+
+impl<const N: usize> Sprite<N> {
+    /// Parse one row per ring, from the centre outwards, with one character per step.
+    ///
+    /// `.` and spaces are empty, any other ASCII character is the colour key
+    /// of that cell. Panics, and so fails to compile in a const, on rows or
+    /// steps beyond the faceplate and on cells with different keys that share
+    /// an LED. `N` must be [`cell_count`] of the same rows.
+    pub const fn parse(rows: &[&str]) -> Self {
+        let mut cells = [SpriteCell { index: 0, key: 0 }; N];
+        let mut len = 0;
+        let mut ring = 0;
+        while ring < rows.len() {
+            let row = rows[ring].as_bytes();
+            let mut step = 0;
+            while step < row.len() {
+                if let Some(index) = cell_index(ring, step, row[step]) {
+                    let key = row[step];
+                    match find(&cells, len, index) {
+                        Some(existing) => {
+                            assert!(
+                                cells[existing].key == key,
+                                "cells with different keys share an LED"
+                            );
+                        }
+                        None => {
+                            cells[len] = SpriteCell { index, key };
+                            len += 1;
+                        }
+                    }
+                }
+                step += 1;
+            }
+            ring += 1;
+        }
+        assert!(len == N, "sprite length does not match its diagram");
+        Self { cells }
+    }
+
+    pub const fn cells(&self) -> &[SpriteCell; N] {
+        &self.cells
+    }
+
+    /// Strip indices of every LED drawn with `key`.
+    pub fn indices(&self, key: u8) -> impl Iterator<Item = u8> + '_ {
+        self.cells
+            .iter()
+            .filter(move |cell| cell.key == key)
+            .map(|cell| cell.index)
+    }
+
+    /// Colour every LED drawn with `key`.
+    #[cfg(feature = "smart-leds")]
+    pub fn draw(&self, canvas: &mut crate::PolarCanvas, key: u8, color: smart_leds::RGB8) {
+        for index in self.indices(key) {
+            canvas.leds_mut()[index as usize] = color;
+        }
+    }
+}
+
+/// Number of distinct LEDs lit by a diagram, for sizing a [`Sprite`].
+pub const fn cell_count(rows: &[&str]) -> usize {
+    let mut seen = [false; 256];
+    let mut count = 0;
+    let mut ring = 0;
+    while ring < rows.len() {
+        let row = rows[ring].as_bytes();
+        let mut step = 0;
+        while step < row.len() {
+            if let Some(index) = cell_index(ring, step, row[step]) {
+                if !seen[index as usize] {
+                    seen[index as usize] = true;
+                    count += 1;
+                }
+            }
+            step += 1;
+        }
+        ring += 1;
+    }
+    count
+}
+
+const fn cell_index(ring: usize, step: usize, key: u8) -> Option<u8> {
+    if key == b'.' || key == b' ' {
+        return None;
+    }
+    assert!(key.is_ascii(), "colour keys must be ASCII");
+    assert!(ring < RING_COUNT, "row beyond the outer ring");
+    assert!(step < STEP_COUNT, "cell beyond the last step");
+    Some(LAYOUT.index_of(Coordinate::new(ring as u8, step as u8)))
+}
+
+const fn find(cells: &[SpriteCell], len: usize, index: u8) -> Option<usize> {
+    let mut i = 0;
+    while i < len {
+        if cells[i].index == index {
+            return Some(i);
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Build a [`Sprite`] at compile time from a text diagram.
+///
+/// Each string is one ring, from the centre outwards, with one character
+/// per step starting at step 0. `.` and spaces are empty, other characters
+/// are colour keys.
+///
+/// ```
+/// use tracker_mapper::polar_sprite;
+///
+/// let face = polar_sprite!(
+///     "n..............."
+///     "......n.n.n....."
+///     "..ee..........ee"
+///     "...e.mmmmmmm..e."
+/// );
+/// assert_eq!(face.indices(b'e').count(), 6);
+/// ```
+///
+/// Cells off the faceplate do not compile:
+///
+/// ```compile_fail
+/// let dot = tracker_mapper::polar_sprite!("................x");
+/// ```
+///
+/// Neither do different keys on one shared LED, like steps 0 and 1 of ring 1:
+///
+/// ```compile_fail
+/// let clash = tracker_mapper::polar_sprite!("................" "ab..............");
+/// ```
+#[macro_export]
+macro_rules! polar_sprite {
+    ($($row:literal)*) => {{
+        const ROWS: &[&str] = &[$($row),*];
+        const SPRITE: $crate::Sprite<{ $crate::sprite_cell_count(ROWS) }> =
+            $crate::Sprite::parse(ROWS);
+        SPRITE
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::index_of;
+
+    #[test]
+    fn parses_diagram() {
+        let sprite = polar_sprite!(
+            "x..............."
+            "yy..y..........."
+            "................"
+            "................"
+            "z..............z"
+        );
+        let cells: Vec<_> = sprite.cells().iter().map(|c| (c.index, c.key)).collect();
+        assert_eq!(
+            cells,
+            [
+                (56, b'x'),
+                (index_of(Coordinate::new(1, 0)), b'y'),
+                (index_of(Coordinate::new(1, 4)), b'y'),
+                (15, b'z'),
+                (0, b'z'),
+            ]
+        );
+    }
+
+    #[test]
+    fn selects_by_key() {
+        let sprite = polar_sprite!("" "" "ab.a" "  b");
+        assert_eq!(sprite.indices(b'a').collect::<Vec<_>>(), [47, 44]);
+        assert_eq!(sprite.indices(b'b').collect::<Vec<_>>(), [46, 29]);
+        assert_eq!(sprite.indices(b'c').count(), 0);
+    }
+
+    #[test]
+    fn counts_shared_leds_once() {
+        assert_eq!(cell_count(&["xxxxxxxxxxxxxxxx", "xx"]), 2);
+        assert_eq!(cell_count(&[]), 0);
+    }
+
+    #[test]
+    #[should_panic = "cells with different keys share an LED"]
+    fn rejects_clashing_keys() {
+        Sprite::<1>::parse(&["ab"]);
+    }
+
+    #[test]
+    #[should_panic = "row beyond the outer ring"]
+    fn rejects_rows_beyond_outer_ring() {
+        Sprite::<1>::parse(&["", "", "", "", "", "x"]);
+    }
+}
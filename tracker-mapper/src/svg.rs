@@ -0,0 +1,154 @@
+use std::fmt::Write;
+use std::string::String;
+
+use smart_leds::RGB8;
+
+use crate::{Position, NUM_LEDS, POSITIONS, RING_COUNT, RING_PITCH_MM};
+
+/// Diameter of one LED footprint in millimetres.
+const LED_DIAMETER_MM: f32 = 5.0;
+
+/// Space between the outer ring and the edge of the board in millimetres.
+const BOARD_MARGIN_MM: f32 = 6.0;
+
+/// Draw a frame as an SVG of the faceplate, with every LED at its position on the PCB.
+///
+/// The image is in millimetres, seen from the LED side with step 0 at the top.
+/// LEDs that are off are drawn as dark outlines.
+pub fn render_svg(frame: &[RGB8; NUM_LEDS]) -> String {
+    let board_radius = (RING_COUNT - 1) as f32 * RING_PITCH_MM + BOARD_MARGIN_MM;
+    let size = 2.0 * board_radius;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}" width="{}mm" height="{}mm">"#,
+        number(-board_radius),
+        number(-board_radius),
+        number(size),
+        number(size),
+        number(size),
+        number(size),
+    );
+    let _ = writeln!(
+        svg,
+        r##"  <circle cx="0" cy="0" r="{}" fill="#101010"/>"##,
+        number(board_radius)
+    );
+    for (index, (color, Position { x, y })) in frame.iter().zip(POSITIONS.iter()).enumerate() {
+        let style = if *color == RGB8::default() {
+            String::from(r##"fill="none" stroke="#404040" stroke-width="0.3""##)
+        } else {
+            format!(r##"fill="#{:02x}{:02x}{:02x}""##, color.r, color.g, color.b)
+        };
+        let _ = writeln!(
+            svg,
+            r#"  <circle id="led{index}" cx="{}" cy="{}" r="{}" {style}/>"#,
+            number(*x),
+            // SVG counts y downwards, the faceplate upwards.
+            number(-*y),
+            number(LED_DIAMETER_MM / 2.0),
+        );
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Two decimals without a sign on zero, so the output is stable across platforms.
+fn number(value: f32) -> String {
+    let rounded = format!("{value:.2}");
+    if rounded == "-0.00" {
+        return String::from("0.00");
+    }
+    rounded
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{blend, rotate_frame, Coordinate, Orientation, PolarCanvas};
+    use smart_leds::colors::{BLUE_VIOLET, FIREBRICK, GAINSBORO, ORANGE_RED, WHITE, YELLOW};
+    use test_case::test_case;
+
+    /// Compare against `golden/<name>.svg`, or rewrite it when `UPDATE_GOLDEN` is set.
+    fn assert_golden(name: &str, frame: &[RGB8; NUM_LEDS]) {
+        let path = format!("{}/golden/{name}.svg", env!("CARGO_MANIFEST_DIR"));
+        let svg = render_svg(frame);
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            std::fs::write(&path, &svg).unwrap();
+            return;
+        }
+        let golden = std::fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("{path}: {e}, run with UPDATE_GOLDEN=1 to create it"));
+        assert_eq!(svg, golden, "{path} is out of date");
+    }
+
+    fn smile() -> PolarCanvas {
+        let mut canvas = PolarCanvas::new();
+        for (ring, step) in [(0, 0), (1, 8), (1, 6), (1, 10)] {
+            canvas.set(Coordinate::new(ring, step), YELLOW);
+        }
+        canvas.draw_arc(3, 5, 11, FIREBRICK);
+        for (ring, step) in [(2, 15), (2, 14), (3, 14), (2, 2), (2, 3), (3, 3)] {
+            canvas.set(Coordinate::new(ring, step), GAINSBORO);
+        }
+        canvas
+    }
+
+    fn spiral(distance: u8) -> PolarCanvas {
+        let mut canvas = PolarCanvas::new();
+        for angle in 0..16 {
+            let coordinate = Coordinate::from_world_coordinates(distance, angle * (360 / 16));
+            canvas.set(coordinate, ORANGE_RED);
+        }
+        canvas
+    }
+
+    #[test]
+    fn renders_dark_frame() {
+        assert_golden("dark", PolarCanvas::new().leds());
+    }
+
+    #[test]
+    fn renders_smile() {
+        assert_golden("smile", smile().leds());
+    }
+
+    #[test]
+    fn renders_mirrored_smile() {
+        let frame = Orientation::new(4, true).apply_frame(smile().leds());
+        assert_golden("smile-mirrored-north-4", &frame);
+    }
+
+    #[test_case(1)]
+    #[test_case(4)]
+    fn renders_spiral_ring(distance: u8) {
+        assert_golden(&format!("spiral-{distance}"), spiral(distance).leds());
+    }
+
+    #[test]
+    fn renders_sector() {
+        let mut canvas = PolarCanvas::new();
+        canvas.draw_sector(14, 2, 1..=4, BLUE_VIOLET);
+        canvas.draw_spoke(8, 0..=4, WHITE);
+        assert_golden("sector", &rotate_frame(canvas.leds(), 1));
+    }
+
+    #[test]
+    fn renders_blended_pointer() {
+        let mut frame = [RGB8::default(); NUM_LEDS];
+        for (index, weight) in blend(3.3, 100.0).iter() {
+            let level = (weight * 255.0) as u8;
+            frame[index as usize] = RGB8::new(level, level / 4, 0);
+        }
+        assert_golden("blend", &frame);
+    }
+
+    #[test_case(0.0 => "0.00")]
+    #[test_case(-0.001 => "0.00")]
+    #[test_case(-1.5 => "-1.50")]
+    #[test_case(27.999 => "28.00")]
+    fn formats_number(value: f32) -> String {
+        number(value)
+    }
+}
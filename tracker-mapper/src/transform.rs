@@ -0,0 +1,188 @@
+use crate::{index_of, Coordinate, LEDS, NUM_LEDS, STEP_ANGLE, STEP_COUNT};
+
+impl Coordinate {
+    /// Turn clockwise by `steps`, or counter-clockwise if negative.
+    pub const fn rotate(self, steps: i8) -> Self {
+        let step = (self.step as i16 + steps as i16).rem_euclid(STEP_COUNT as i16);
+        Self::new(self.ring, step as u8)
+    }
+
+    /// Turn clockwise by `degrees`, rounded to the nearest step.
+    pub fn rotate_degrees(self, degrees: f32) -> Self {
+        let steps = degrees / STEP_ANGLE;
+        let steps = if steps < 0.0 {
+            steps - 0.5
+        } else {
+            steps + 0.5
+        } as i32;
+        self.rotate(steps.rem_euclid(STEP_COUNT as i32) as i8)
+    }
+
+    /// Swap left and right, keeping step 0 in place.
+    pub const fn mirror(self) -> Self {
+        Self::new(
+            self.ring,
+            ((STEP_COUNT - self.step as usize) % STEP_COUNT) as u8,
+        )
+    }
+}
+
+/// How the PCB sits in its enclosure.
+///
+/// Effects draw in view coordinates, where step 0 is "forward" and the LEDs
+/// face the viewer. The orientation moves that onto the PCB.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Orientation {
+    /// PCB step that points forward.
+    pub north: u8,
+    /// Whether the faceplate is seen through the back of the PCB.
+    pub mirrored: bool,
+}
+
+impl Orientation {
+    pub const fn new(north: u8, mirrored: bool) -> Self {
+        Self { north, mirrored }
+    }
+
+    pub const fn to_pcb(&self, coordinate: Coordinate) -> Coordinate {
+        let coordinate = if self.mirrored {
+            coordinate.mirror()
+        } else {
+            coordinate
+        };
+        coordinate.rotate((self.north as usize % STEP_COUNT) as i8)
+    }
+
+    pub const fn to_view(&self, coordinate: Coordinate) -> Coordinate {
+        let coordinate = coordinate.rotate(-((self.north as usize % STEP_COUNT) as i8));
+        if self.mirrored {
+            coordinate.mirror()
+        } else {
+            coordinate
+        }
+    }
+
+    /// Move a frame drawn in view coordinates onto the PCB.
+    pub fn apply_frame<T: Copy>(&self, frame: &[T; NUM_LEDS]) -> [T; NUM_LEDS] {
+        map_frame(frame, |coordinate| self.to_view(coordinate))
+    }
+}
+
+/// Turn a whole frame clockwise by `steps`.
+pub fn rotate_frame<T: Copy>(frame: &[T; NUM_LEDS], steps: i8) -> [T; NUM_LEDS] {
+    map_frame(frame, |coordinate| coordinate.rotate(steps.wrapping_neg()))
+}
+
+/// Swap left and right in a whole frame.
+pub fn mirror_frame<T: Copy>(frame: &[T; NUM_LEDS]) -> [T; NUM_LEDS] {
+    map_frame(frame, Coordinate::mirror)
+}
+
+/// Build a frame where every LED takes the colour found at `source` of its
+/// first coordinate, so folded LEDs pick up whatever lands on their own step.
+fn map_frame<T: Copy>(
+    frame: &[T; NUM_LEDS],
+    source: impl Fn(Coordinate) -> Coordinate,
+) -> [T; NUM_LEDS] {
+    let mut result = *frame;
+    for (led, target) in LEDS.iter().zip(result.iter_mut()) {
+        let first = Coordinate::new(led.ring, led.first_step);
+        *target = frame[index_of(source(first)) as usize];
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_case::test_case;
+
+    fn frame() -> [u8; NUM_LEDS] {
+        core::array::from_fn(|index| index as u8)
+    }
+
+    #[test_case(Coordinate::new(3, 0), 1 => Coordinate::new(3, 1))]
+    #[test_case(Coordinate::new(3, 15), 1 => Coordinate::new(3, 0))]
+    #[test_case(Coordinate::new(3, 0), -1 => Coordinate::new(3, 15))]
+    #[test_case(Coordinate::new(2, 5), 16 => Coordinate::new(2, 5))]
+    #[test_case(Coordinate::new(2, 5), -35 => Coordinate::new(2, 2))]
+    fn rotates_coordinate(coordinate: Coordinate, steps: i8) -> Coordinate {
+        coordinate.rotate(steps)
+    }
+
+    #[test_case(Coordinate::new(4, 0), 90.0 => Coordinate::new(4, 4))]
+    #[test_case(Coordinate::new(4, 0), 11.0 => Coordinate::new(4, 0))]
+    #[test_case(Coordinate::new(4, 0), 12.0 => Coordinate::new(4, 1))]
+    #[test_case(Coordinate::new(4, 0), -12.0 => Coordinate::new(4, 15))]
+    #[test_case(Coordinate::new(4, 3), 720.0 => Coordinate::new(4, 3))]
+    fn rotates_coordinate_by_degrees(coordinate: Coordinate, degrees: f32) -> Coordinate {
+        coordinate.rotate_degrees(degrees)
+    }
+
+    #[test_case(Coordinate::new(4, 0) => Coordinate::new(4, 0))]
+    #[test_case(Coordinate::new(4, 4) => Coordinate::new(4, 12))]
+    #[test_case(Coordinate::new(4, 8) => Coordinate::new(4, 8))]
+    #[test_case(Coordinate::new(1, 3) => Coordinate::new(1, 13))]
+    fn mirrors_coordinate(coordinate: Coordinate) -> Coordinate {
+        coordinate.mirror()
+    }
+
+    #[test]
+    fn orientation_round_trips() {
+        for orientation in [
+            Orientation::default(),
+            Orientation::new(5, false),
+            Orientation::new(11, true),
+            Orientation::new(0, true),
+        ] {
+            for coordinate in Coordinate::all() {
+                let pcb = orientation.to_pcb(coordinate);
+                assert_eq!(orientation.to_view(pcb), coordinate);
+            }
+        }
+    }
+
+    #[test]
+    fn rotates_frame() {
+        let rotated = rotate_frame(&frame(), 4);
+        // Step 0 of the outer ring moves to step 4.
+        assert_eq!(rotated[index_of(Coordinate::new(4, 4)) as usize], 15);
+        // The centre stays where it is.
+        assert_eq!(rotated[56], 56);
+        // Ring 1 turns by two of its LEDs.
+        assert_eq!(rotated[index_of(Coordinate::new(1, 4)) as usize], 55);
+        assert_eq!(rotate_frame(&rotated, -4), frame());
+    }
+
+    #[test_case(i8::MIN, 0)]
+    #[test_case(i8::MAX, -1)]
+    #[test_case(-17, -1)]
+    fn rotates_frame_by_any_steps(steps: i8, equivalent: i8) {
+        assert_eq!(
+            rotate_frame(&frame(), steps),
+            rotate_frame(&frame(), equivalent)
+        );
+    }
+
+    #[test]
+    fn mirrors_frame() {
+        let mirrored = mirror_frame(&frame());
+        assert_eq!(mirrored[index_of(Coordinate::new(4, 4)) as usize], 3);
+        assert_eq!(mirrored[index_of(Coordinate::new(1, 2)) as usize], 48);
+        assert_eq!(mirror_frame(&mirrored), frame());
+    }
+
+    #[test]
+    fn applies_orientation_to_frame() {
+        let mut view = [0u8; NUM_LEDS];
+        view[index_of(Coordinate::new(4, 2)) as usize] = 1;
+
+        let pcb = Orientation::new(4, true).apply_frame(&view);
+        let lit: Vec<_> = (0..NUM_LEDS).filter(|i| pcb[*i] == 1).collect();
+        assert_eq!(lit, [index_of(Coordinate::new(4, 2)) as usize]);
+
+        let pcb = Orientation::new(4, false).apply_frame(&view);
+        let lit: Vec<_> = (0..NUM_LEDS).filter(|i| pcb[*i] == 1).collect();
+        assert_eq!(lit, [index_of(Coordinate::new(4, 6)) as usize]);
+    }
+}